@@ -1,7 +1,41 @@
-use clap::{Parser, Subcommand};
-use folding_analysis_rs::{load_contacts_from_csv, load_results_from_csv, PdbTrajectory, Trajectory};
+use clap::{Parser, Subcommand, ValueEnum};
+use folding_analysis_rs::{
+    batch_process_replicas, consensus_formation_order, format_name, is_bin_format, load_contacts,
+    load_results_from_bin, load_results_from_csv, open_trajectory, pathway_statistics,
+    save_batch_aggregate_to_csv, select_frame_range, Hysteresis, MeanClusterFillingOperator,
+    OutputFormat, PdbTrajectory, Trajectory,
+};
 use std::path::{Path, PathBuf};
 
+/// CLI selector for the saved-file backend, mapped onto [`OutputFormat`].
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Format {
+    Csv,
+    Parquet,
+    Bin,
+}
+
+impl From<Format> for OutputFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Csv => OutputFormat::Csv,
+            Format::Parquet => OutputFormat::Parquet,
+            Format::Bin => OutputFormat::Bin,
+        }
+    }
+}
+
+/// Load parsed per-frame results from `input`, auto-detecting [`crate::bin_io`]'s compact
+/// binary format by magic bytes so `Summarize`/`Smooth` accept either a `Read --format bin`
+/// output or plain CSV without the caller needing to say which.
+fn load_parsed_input(input: &Path) -> Result<Vec<folding_analysis_rs::FrameResult>, String> {
+    if is_bin_format(input) {
+        load_results_from_bin(input)
+    } else {
+        load_results_from_csv(input)
+    }
+}
+
 /// Command-line tool for analyzing protein folding trajectories
 #[derive(Parser)]
 #[command(name = "folding-analysis")]
@@ -26,19 +60,51 @@ enum Commands {
         /// Multiplier for native distance cutoff (default: 1.2)
         #[arg(long, default_value_t = 1.2)]
         cutoff_distance: f64,
-        
+
+        /// Steepness (Å⁻¹) of the smooth Best–Hummer Q (default: 5.0)
+        #[arg(long, default_value_t = 5.0)]
+        beta: f64,
+
+        /// Tolerance multiplier on the native distance for the smooth Q (default: 1.2)
+        #[arg(long, default_value_t = 1.2)]
+        lambda: f64,
+
         /// Maximum number of frames to process (default: all frames)
         #[arg(long)]
         max_frames: Option<usize>,
-        
-        /// Output CSV path (default: auto-generated from trajectory path)
+
+        /// Reservoir-sample this many frames instead of truncating to the first max-frames
+        #[arg(long)]
+        sample_frames: Option<usize>,
+
+        /// RNG seed for reproducible reservoir sampling
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Skip frames before this true trajectory index (default: from the start)
+        #[arg(long)]
+        start_frame: Option<i32>,
+
+        /// Stop after this true trajectory index (default: through the end)
+        #[arg(long)]
+        end_frame: Option<i32>,
+
+        /// Keep only every Nth frame within the start/end window (default: all)
+        #[arg(long)]
+        stride: Option<usize>,
+
+        /// Output path (default: auto-generated from trajectory path)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Output backend: plain csv, columnar parquet, or the compact bin format (default: csv)
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
     },
-    
+
     /// Summarize trajectory by calculating mean cluster filling fractions in windows
     Summarize {
-        /// Path to the parsed trajectory CSV (from read command)
+        /// Path to the parsed trajectory results (from read command; csv or bin auto-detected)
         #[arg(short, long)]
         input: PathBuf,
         
@@ -49,15 +115,39 @@ enum Commands {
         /// Optional cutoff for binary conversion (values >= cutoff become 1, values < cutoff become 0)
         #[arg(long)]
         cutoff: Option<f64>,
-        
-        /// Output CSV path (default: auto-generated from input path)
+
+        /// Number of bootstrap resamples for a 95% CI on each cluster mean (default: none)
+        #[arg(long)]
+        bootstrap: Option<usize>,
+
+        /// Restrict to frames (or, with --dt, simulation time) at or after this point
+        #[arg(long)]
+        start: Option<f64>,
+
+        /// Restrict to frames (or, with --dt, simulation time) at or before this point
+        #[arg(long)]
+        end: Option<f64>,
+
+        /// Keep only every Nth frame surviving the start/end filter (default: all)
+        #[arg(long)]
+        stride: Option<usize>,
+
+        /// Time per frame, making --start/--end interpreted as simulation time rather than frame index
+        #[arg(long)]
+        dt: Option<f64>,
+
+        /// Output path (default: auto-generated from input path)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Output backend: plain csv or columnar parquet (default: csv)
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
     },
-    
+
     /// Smooth trajectory data by calculating running averages
     Smooth {
-        /// Path to the parsed trajectory CSV (from read command)
+        /// Path to the parsed trajectory results (from read command; csv or bin auto-detected)
         #[arg(short, long)]
         input: PathBuf,
         
@@ -65,20 +155,121 @@ enum Commands {
         #[arg(short, long, default_value_t = 100)]
         window_size: usize,
         
-        /// Output CSV path (default: auto-generated from input path)
+        /// Output path (default: auto-generated from input path)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Output backend: plain csv or columnar parquet (default: csv)
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
     },
-    
+
     /// Classify trajectory by determining cluster formation order
     Classify {
         /// Path to the summary CSV file (binary representation from summarize with cutoff)
         #[arg(short, long)]
         input: PathBuf,
-        
+
         /// Output text file path (default: auto-generated from input path)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Debounce cluster formation with Schmitt-trigger hysteresis and gap bridging
+        #[arg(long)]
+        hysteresis: bool,
+
+        /// Upper occupancy threshold marking a cluster formed (hysteresis mode)
+        #[arg(long, default_value_t = 1.0)]
+        t_up: f64,
+
+        /// Lower occupancy threshold marking a cluster broken (hysteresis mode)
+        #[arg(long, default_value_t = 0.5)]
+        t_down: f64,
+
+        /// Maximum sub-threshold run length bridged as still formed (hysteresis mode)
+        #[arg(long, default_value_t = 1)]
+        min_gap: usize,
+    },
+
+    /// Process many replica trajectories concurrently, aggregating mean cluster filling
+    /// across replicas into one CSV
+    BatchRead {
+        /// Glob pattern matching replica trajectory files (e.g. `run_*/traj.pdb`)
+        #[arg(short, long)]
+        pattern: String,
+
+        /// Path to the shared contacts CSV (must contain columns: i, j, r, cluster)
+        #[arg(short, long)]
+        contacts: PathBuf,
+
+        /// Multiplier for native distance cutoff (default: 1.2)
+        #[arg(long, default_value_t = 1.2)]
+        cutoff_distance: f64,
+
+        /// Steepness (Å⁻¹) of the smooth Best–Hummer Q (default: 5.0)
+        #[arg(long, default_value_t = 5.0)]
+        beta: f64,
+
+        /// Tolerance multiplier on the native distance for the smooth Q (default: 1.2)
+        #[arg(long, default_value_t = 1.2)]
+        lambda: f64,
+
+        /// Maximum number of frames to process per replica (default: all frames)
+        #[arg(long)]
+        max_frames: Option<usize>,
+
+        /// Number of worker threads (default: 0, meaning use all available cores)
+        #[arg(long, default_value_t = 0)]
+        workers: usize,
+
+        /// Output path for the aggregated mean-filling CSV (default: auto-generated next
+        /// to the first matched replica)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Output backend for each replica's own parsed results: plain csv, columnar parquet,
+        /// or the compact bin format
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+    },
+
+    /// Determine a consensus cluster-formation order across replicate trajectories
+    Consensus {
+        /// Paths to each replica's summary CSV file (binary representation from summarize with cutoff)
+        #[arg(short, long, num_args = 1.., required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output CSV path (default: auto-generated from the first input path)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Debounce cluster formation with Schmitt-trigger hysteresis and gap bridging
+        #[arg(long)]
+        hysteresis: bool,
+
+        /// Upper occupancy threshold marking a cluster formed (hysteresis mode)
+        #[arg(long, default_value_t = 1.0)]
+        t_up: f64,
+
+        /// Lower occupancy threshold marking a cluster broken (hysteresis mode)
+        #[arg(long, default_value_t = 0.5)]
+        t_down: f64,
+
+        /// Maximum sub-threshold run length bridged as still formed (hysteresis mode)
+        #[arg(long, default_value_t = 1)]
+        min_gap: usize,
+    },
+
+    /// Aggregate many replicas' Classify output into a cross-replica pathway-frequency report
+    Aggregate {
+        /// Glob pattern matching `_class.txt` files (e.g. `run_*/traj_class.txt`)
+        #[arg(short, long)]
+        pattern: String,
+
+        /// Output path for the pathway-frequency CSV (default: auto-generated next to the
+        /// first matched class file; per-cluster ranks are saved alongside it)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
@@ -90,15 +281,32 @@ fn main() {
             trajectory,
             contacts,
             cutoff_distance,
+            beta,
+            lambda,
             max_frames,
+            sample_frames,
+            seed,
+            start_frame,
+            end_frame,
+            stride,
             output,
+            format,
         } => {
             println!("Reading trajectory: {:?}", trajectory);
             println!("Using contacts: {:?}", contacts);
             println!("Cutoff distance: {}", cutoff_distance);
-            
+
+            // Report the format picked by extension and its frame count up front, since
+            // binary formats (DCD/XTC) and GRO silently look like "just a trajectory" to
+            // the rest of the CLI otherwise.
+            println!("Format: {}", format_name(&trajectory));
+            match open_trajectory(&trajectory).and_then(|f| f.frame_count()) {
+                Ok(count) => println!("Frame count: {}", count),
+                Err(e) => eprintln!("⚠️  Could not determine frame count: {}", e),
+            }
+
             // Load contacts
-            let contacts_vec = match load_contacts_from_csv(contacts.to_str().unwrap()) {
+            let contacts_vec = match load_contacts(contacts.to_str().unwrap()) {
                 Ok(c) => {
                     println!("✅ Loaded {} contacts", c.len());
                     c
@@ -116,8 +324,17 @@ fn main() {
             match traj.read_trajectory(
                 &contacts_vec,
                 cutoff_distance,
+                beta,
+                lambda,
                 max_frames,
+                sample_frames,
+                seed,
+                start_frame,
+                end_frame,
+                stride,
                 output.as_deref(),
+                true,
+                format.into(),
             ) {
                 Ok(results) => {
                     println!("✅ Successfully processed {} frames", results.len());
@@ -142,30 +359,45 @@ fn main() {
             input,
             window_size,
             cutoff,
+            bootstrap,
+            start,
+            end,
+            stride,
+            dt,
             output,
+            format,
         } => {
             println!("Summarizing trajectory: {:?}", input);
             println!("Window size: {}", window_size);
             if let Some(c) = cutoff {
                 println!("Binary cutoff: {}", c);
             }
-            
-            // Load results from CSV
-            let results = match load_results_from_csv(&input) {
+
+            // Load parsed results, auto-detecting the bin format written by `Read --format bin`
+            let results = match load_parsed_input(&input) {
                 Ok(r) => {
-                    println!("✅ Loaded {} frames from CSV", r.len());
+                    println!("✅ Loaded {} frames", r.len());
                     r
                 }
                 Err(e) => {
-                    eprintln!("❌ Error loading CSV: {}", e);
+                    eprintln!("❌ Error loading results: {}", e);
                     std::process::exit(1);
                 }
             };
-            
+
+            // Restrict to a frame or time range before summarizing, if requested.
+            let results = if start.is_some() || end.is_some() || stride.is_some() {
+                let subset = select_frame_range(&results, start, end, stride, dt);
+                println!("✅ Restricted to {} frames in range", subset.len());
+                subset
+            } else {
+                results
+            };
+
             // Create trajectory with input path (for output path generation)
             let traj = PdbTrajectory::new(&input);
-            
-            match traj.summarize_trajectory(Some(&results), window_size, cutoff, output.as_deref()) {
+
+            match traj.summarize_trajectory(Some(&results), window_size, cutoff, output.as_deref(), false, format.into(), bootstrap) {
                 Ok(summaries) => {
                     println!("✅ Successfully summarized {} windows", summaries.len());
                     if let Some(output_path) = output {
@@ -194,26 +426,27 @@ fn main() {
             input,
             window_size,
             output,
+            format,
         } => {
             println!("Smoothing trajectory: {:?}", input);
             println!("Window size: {}", window_size);
             
-            // Load results from CSV
-            let results = match load_results_from_csv(&input) {
+            // Load parsed results, auto-detecting the bin format written by `Read --format bin`
+            let results = match load_parsed_input(&input) {
                 Ok(r) => {
-                    println!("✅ Loaded {} frames from CSV", r.len());
+                    println!("✅ Loaded {} frames", r.len());
                     r
                 }
                 Err(e) => {
-                    eprintln!("❌ Error loading CSV: {}", e);
+                    eprintln!("❌ Error loading results: {}", e);
                     std::process::exit(1);
                 }
             };
-            
+
             // Create trajectory with input path (for output path generation)
             let traj = PdbTrajectory::new(&input);
-            
-            match traj.smooth(Some(&results), window_size, output.as_deref()) {
+
+            match traj.smooth(Some(&results), window_size, output.as_deref(), false, format.into()) {
                 Ok(smoothed) => {
                     println!("✅ Successfully smoothed {} frames", smoothed.len());
                     if let Some(output_path) = output {
@@ -236,12 +469,26 @@ fn main() {
         Commands::Classify {
             input,
             output,
+            hysteresis,
+            t_up,
+            t_down,
+            min_gap,
         } => {
             println!("Classifying trajectory: {:?}", input);
-            
+
             let traj = PdbTrajectory::new(&input);
-            
-            match traj.classify(Some(&input), output.as_deref()) {
+
+            let hysteresis = if hysteresis {
+                Some(Hysteresis {
+                    t_up,
+                    t_down,
+                    min_gap,
+                })
+            } else {
+                None
+            };
+
+            match traj.classify(Some(&input), output.as_deref(), hysteresis) {
                 Ok(order) => {
                     println!("✅ Cluster formation order: {:?}", order);
                     if let Some(output_path) = output {
@@ -260,6 +507,159 @@ fn main() {
                 }
             }
         }
+
+        Commands::BatchRead {
+            pattern,
+            contacts,
+            cutoff_distance,
+            beta,
+            lambda,
+            max_frames,
+            workers,
+            output,
+            format,
+        } => {
+            let paths: Vec<PathBuf> = match glob::glob(&pattern) {
+                Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+                Err(e) => {
+                    eprintln!("❌ Invalid glob pattern '{}': {}", pattern, e);
+                    std::process::exit(1);
+                }
+            };
+            if paths.is_empty() {
+                eprintln!("❌ No trajectories matched pattern '{}'", pattern);
+                std::process::exit(1);
+            }
+            println!("Found {} replica trajectories", paths.len());
+
+            let contacts_vec = match load_contacts(contacts.to_str().unwrap()) {
+                Ok(c) => {
+                    println!("✅ Loaded {} contacts", c.len());
+                    c
+                }
+                Err(e) => {
+                    eprintln!("❌ Error loading contacts: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let op = MeanClusterFillingOperator {
+                contacts: &contacts_vec,
+                cutoff_distance,
+                beta,
+                lambda,
+                max_frames,
+                output_format: format.into(),
+            };
+
+            match batch_process_replicas(&paths, &op, workers, |done, total, path| {
+                println!("✅ [{}/{}] Processed {:?}", done, total, path);
+            }) {
+                Ok(acc) => {
+                    let output_path = output.unwrap_or_else(|| {
+                        let dir = paths[0].parent().unwrap_or(Path::new(".")).to_path_buf();
+                        dir.join("batch_aggregate.csv")
+                    });
+                    match save_batch_aggregate_to_csv(&acc, &output_path) {
+                        Ok(()) => println!("📄 Aggregate saved to: {:?}", output_path),
+                        Err(e) => {
+                            eprintln!("❌ Error saving aggregate: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ Error in batch processing: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Consensus {
+            inputs,
+            output,
+            hysteresis,
+            t_up,
+            t_down,
+            min_gap,
+        } => {
+            println!("Computing consensus formation order across {} replicas", inputs.len());
+
+            let hysteresis = if hysteresis {
+                Some(Hysteresis {
+                    t_up,
+                    t_down,
+                    min_gap,
+                })
+            } else {
+                None
+            };
+
+            match consensus_formation_order(&inputs, hysteresis, output.as_deref()) {
+                Ok(consensus) => {
+                    println!("✅ Consensus formation order: {:?}", consensus.order);
+                    if let Some(output_path) = output {
+                        println!("📄 Consensus saved to: {:?}", output_path);
+                    } else {
+                        let base = inputs[0].file_stem().unwrap().to_str().unwrap();
+                        let base = base
+                            .strip_suffix("_summary_binary")
+                            .or_else(|| base.strip_suffix("_summary"))
+                            .unwrap_or(base);
+                        let dir = inputs[0].parent().unwrap_or(std::path::Path::new("."));
+                        let auto_path = dir.join(format!("{}_consensus_class.csv", base));
+                        println!("📄 Consensus saved to: {:?}", auto_path);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ Error computing consensus: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Aggregate { pattern, output } => {
+            let paths: Vec<PathBuf> = match glob::glob(&pattern) {
+                Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+                Err(e) => {
+                    eprintln!("❌ Invalid glob pattern '{}': {}", pattern, e);
+                    std::process::exit(1);
+                }
+            };
+            if paths.is_empty() {
+                eprintln!("❌ No class files matched pattern '{}'", pattern);
+                std::process::exit(1);
+            }
+            println!("Found {} replica class files", paths.len());
+
+            match pathway_statistics(&paths, output.as_deref()) {
+                Ok(stats) => {
+                    println!("✅ Found {} distinct formation pathways", stats.pathways.len());
+                    if let Some(top) = stats.pathways.first() {
+                        println!(
+                            "   Most common: {:?} ({}/{}, {:.1}%)",
+                            top.order,
+                            top.count,
+                            paths.len(),
+                            top.fraction * 100.0
+                        );
+                    }
+                    if let Some(output_path) = output {
+                        println!("📄 Pathway report saved to: {:?}", output_path);
+                    } else {
+                        let base = paths[0].file_stem().unwrap().to_str().unwrap();
+                        let base = base.strip_suffix("_class").unwrap_or(base);
+                        let dir = paths[0].parent().unwrap_or(std::path::Path::new("."));
+                        let auto_path = dir.join(format!("{}_pathways.csv", base));
+                        println!("📄 Pathway report saved to: {:?}", auto_path);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("❌ Error computing pathway statistics: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 }
 
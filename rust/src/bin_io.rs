@@ -0,0 +1,210 @@
+//! Compact, dependency-light binary format for per-frame results.
+//!
+//! `save_results_to_csv` bloats for long trajectories with many clusters, and
+//! `save_results_to_parquet` ([`crate::parquet_io`]) pulls in Arrow to fix that. This module
+//! is a third option: a small hand-rolled columnar layout purpose-built for [`FrameResult`].
+//! A magic-byte + version header records the frame/cluster counts so the file is
+//! self-describing, [`is_bin_format`] lets `Summarize`/`Smooth` auto-detect it
+//! alongside the existing CSV path by sniffing those bytes, and the frame indices and
+//! per-cluster filling fractions are packed as contiguous `u32`/`f32` column blocks rather
+//! than repeated per row.
+//!
+//! ```text
+//! magic        4 bytes    b"FRB1"
+//! version      u32 LE     1
+//! num_frames   u32 LE
+//! num_clusters u32 LE
+//! cluster_ids  num_clusters x i32 LE
+//! frames       num_frames x u32 LE
+//! q            num_frames x f32 LE
+//! cluster_0    num_frames x f32 LE
+//! ...
+//! cluster_N-1  num_frames x f32 LE
+//! ```
+//!
+//! `contacts`, `q_soft`, `contact_list` and `clusters_filling_soft` are not stored — none of
+//! the downstream `summarize`/`smooth` steps read them — and are reconstructed with the same
+//! defaults [`crate::parquet_io::load_results_from_parquet`] uses for an absent column.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::trajectory::FrameResult;
+
+/// Magic bytes identifying this crate's compact binary result format.
+const MAGIC: &[u8; 4] = b"FRB1";
+const VERSION: u32 = 1;
+
+/// Whether `path`'s leading bytes are this format's magic, for auto-detection by callers
+/// that otherwise assume CSV.
+pub fn is_bin_format(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut header = [0u8; 4];
+    file.read_exact(&mut header).is_ok() && &header == MAGIC
+}
+
+/// Sorted, de-duplicated cluster numbers appearing in `results`.
+fn cluster_numbers(results: &[FrameResult]) -> Vec<i32> {
+    let mut set: HashSet<i32> = HashSet::new();
+    for result in results {
+        set.extend(result.clusters_filling.keys().copied());
+    }
+    let mut numbers: Vec<i32> = set.into_iter().collect();
+    numbers.sort();
+    numbers
+}
+
+/// Save per-frame results in the compact binary format described in the module docs.
+pub fn save_results_to_bin(results: &[FrameResult], path: &Path) -> Result<(), String> {
+    let clusters = cluster_numbers(results);
+
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create binary file {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+
+    writer
+        .write_all(MAGIC)
+        .and_then(|_| writer.write_all(&VERSION.to_le_bytes()))
+        .and_then(|_| writer.write_all(&(results.len() as u32).to_le_bytes()))
+        .and_then(|_| writer.write_all(&(clusters.len() as u32).to_le_bytes()))
+        .map_err(|e| format!("Failed to write binary header: {}", e))?;
+
+    for &cluster in &clusters {
+        writer
+            .write_all(&cluster.to_le_bytes())
+            .map_err(|e| format!("Failed to write cluster id: {}", e))?;
+    }
+
+    for result in results {
+        writer
+            .write_all(&(result.frame as u32).to_le_bytes())
+            .map_err(|e| format!("Failed to write frame index: {}", e))?;
+    }
+
+    for result in results {
+        writer
+            .write_all(&(result.q as f32).to_le_bytes())
+            .map_err(|e| format!("Failed to write q column: {}", e))?;
+    }
+
+    for &cluster in &clusters {
+        for result in results {
+            let fraction = result.clusters_filling.get(&cluster).copied().unwrap_or(0.0);
+            writer
+                .write_all(&(fraction as f32).to_le_bytes())
+                .map_err(|e| format!("Failed to write cluster_{} column: {}", cluster, e))?;
+        }
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush binary file: {}", e))?;
+
+    Ok(())
+}
+
+/// Load per-frame results from a file written by [`save_results_to_bin`].
+///
+/// Reconstructs each [`FrameResult`] from the stored `frame`/`q` and per-cluster columns;
+/// `contacts` defaults to `0`, `q_soft` to `q`, `contact_list` to empty and
+/// `clusters_filling_soft` to a copy of `clusters_filling` — the same defaults
+/// [`crate::parquet_io::load_results_from_parquet`] uses when those columns are absent.
+pub fn load_results_from_bin(path: &Path) -> Result<Vec<FrameResult>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open binary file {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|e| format!("Failed to read binary header: {}", e))?;
+    if &magic != MAGIC {
+        return Err(format!(
+            "Not a recognized binary result file (bad magic) at {}",
+            path.display()
+        ));
+    }
+
+    let version = read_u32(&mut reader)?;
+    if version != VERSION {
+        return Err(format!(
+            "Unsupported binary result format version {} (expected {})",
+            version, VERSION
+        ));
+    }
+
+    let num_frames = read_u32(&mut reader)? as usize;
+    let num_clusters = read_u32(&mut reader)? as usize;
+
+    let mut cluster_ids = Vec::with_capacity(num_clusters);
+    for _ in 0..num_clusters {
+        cluster_ids.push(read_i32(&mut reader)?);
+    }
+
+    let mut frames = Vec::with_capacity(num_frames);
+    for _ in 0..num_frames {
+        frames.push(read_u32(&mut reader)? as i32);
+    }
+
+    let mut q_values = Vec::with_capacity(num_frames);
+    for _ in 0..num_frames {
+        q_values.push(read_f32(&mut reader)? as f64);
+    }
+
+    let mut cluster_columns = Vec::with_capacity(num_clusters);
+    for &cluster in &cluster_ids {
+        let mut column = Vec::with_capacity(num_frames);
+        for _ in 0..num_frames {
+            column.push(read_f32(&mut reader)? as f64);
+        }
+        cluster_columns.push((cluster, column));
+    }
+
+    let mut results = Vec::with_capacity(num_frames);
+    for row in 0..num_frames {
+        let mut clusters_filling = std::collections::HashMap::with_capacity(num_clusters);
+        for (cluster, column) in &cluster_columns {
+            clusters_filling.insert(*cluster, column[row]);
+        }
+        let q = q_values[row];
+        results.push(FrameResult {
+            frame: frames[row],
+            contacts: 0,
+            q,
+            q_soft: q,
+            contact_list: Vec::new(),
+            clusters_filling_soft: clusters_filling.clone(),
+            clusters_filling,
+        });
+    }
+
+    Ok(results)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, String> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| format!("Failed to read u32 from binary file: {}", e))?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_i32(reader: &mut impl Read) -> Result<i32, String> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| format!("Failed to read i32 from binary file: {}", e))?;
+    Ok(i32::from_le_bytes(buf))
+}
+
+fn read_f32(reader: &mut impl Read) -> Result<f32, String> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| format!("Failed to read f32 from binary file: {}", e))?;
+    Ok(f32::from_le_bytes(buf))
+}
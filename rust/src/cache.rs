@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::contacts::Contact;
+use crate::trajectory::FrameResult;
+
+/// On-disk bincode cache of the parsed per-frame results, with content-hash invalidation.
+///
+/// Parsing a trajectory and computing per-frame contacts is the expensive step, so
+/// `read_trajectory` serializes the `Vec<FrameResult>` with `bincode` to a `.traj.cache`
+/// sidecar next to the parsed CSV and the downstream `summarize`/`smooth` steps
+/// deserialize it instead of re-parsing the CSV. The cache stores a SHA3-256 digest of the
+/// inputs it was built from (the trajectory bytes, the cutoff, `max_frames` and the contact
+/// map); `read_trajectory` reuses it only when that digest matches. `beta` and `lambda` are
+/// not part of the digest, and the downstream steps ([`load_cache_if_fresh`]) accept any
+/// cache newer than the parsed CSV without checking the digest.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheFile {
+    key: String,
+    results: Vec<FrameResult>,
+}
+
+/// Compute the cache key for a parse: a SHA3-256 digest (hex) of the trajectory file
+/// bytes, the cutoff distance, `max_frames` and the contact map. Any change to those
+/// inputs changes the key.
+pub fn cache_key(
+    trajectory_file: &str,
+    cutoff_distance: f64,
+    max_frames: Option<usize>,
+    contacts: &[Contact],
+) -> String {
+    let mut hasher = Sha3_256::new();
+    if let Ok(bytes) = std::fs::read(trajectory_file) {
+        hasher.update(&bytes);
+    }
+    hasher.update(cutoff_distance.to_le_bytes());
+    // Distinguish "all frames" from an explicit count.
+    hasher.update((max_frames.unwrap_or(usize::MAX) as u64).to_le_bytes());
+    for contact in contacts {
+        hasher.update(contact.i.to_le_bytes());
+        hasher.update(contact.j.to_le_bytes());
+        hasher.update(contact.r.to_le_bytes());
+        hasher.update(contact.cluster.to_le_bytes());
+    }
+    hex_encode(&hasher.finalize())
+}
+
+/// Path of the `.traj.cache` sidecar derived from the trajectory file path.
+pub fn cache_path(trajectory_file: &str) -> PathBuf {
+    let path = Path::new(trajectory_file);
+    let base = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("trajectory");
+    let dir = path.parent().unwrap_or(Path::new("."));
+    dir.join(format!("{}.traj.cache", base))
+}
+
+/// Serialize the parsed results to the `.traj.cache` sidecar with the given key.
+pub fn write_cache(path: &Path, key: &str, results: &[FrameResult]) -> Result<(), String> {
+    let file = CacheFile {
+        key: key.to_string(),
+        results: results.to_vec(),
+    };
+    let bytes = bincode::serialize(&file)
+        .map_err(|e| format!("Failed to serialize result cache: {}", e))?;
+    std::fs::write(path, bytes)
+        .map_err(|e| format!("Failed to write result cache {}: {}", path.display(), e))
+}
+
+/// Load the cached results if the sidecar exists and its key matches `expected_key`.
+/// Returns `None` (rather than an error) on any mismatch or read failure so callers can
+/// transparently fall back to recomputing/parsing the CSV.
+pub fn load_cache(path: &Path, expected_key: &str) -> Option<Vec<FrameResult>> {
+    let bytes = std::fs::read(path).ok()?;
+    let file: CacheFile = bincode::deserialize(&bytes).ok()?;
+    if file.key == expected_key {
+        Some(file.results)
+    } else {
+        None
+    }
+}
+
+/// Load the cached results when the sidecar exists and is newer than `source`.
+///
+/// Used by the downstream steps, which don't hold the cutoff/contacts needed to
+/// recompute the key and instead trust the `.traj.cache` when it is fresher than the
+/// parsed CSV it would otherwise read.
+pub fn load_cache_if_fresh(path: &Path, source: &Path) -> Option<Vec<FrameResult>> {
+    let cache_mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let source_mtime = std::fs::metadata(source).and_then(|m| m.modified()).ok()?;
+    if cache_mtime < source_mtime {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    let file: CacheFile = bincode::deserialize(&bytes).ok()?;
+    Some(file.results)
+}
+
+/// Lower-case hex encoding of a digest.
+fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{:02x}", b);
+    }
+    out
+}
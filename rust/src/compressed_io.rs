@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// Streaming (de)compression backend selected by a path's extension or, for reading,
+/// its magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    None,
+    Gzip,
+    Lz4,
+}
+
+/// gzip magic number (RFC 1952).
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// LZ4 frame format magic number.
+const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+
+fn codec_for_extension(path: &Path) -> Codec {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => Codec::Gzip,
+        Some("lz4") => Codec::Lz4,
+        _ => Codec::None,
+    }
+}
+
+/// Whether `path` names a compressed file by extension, used by callers deciding whether
+/// a zero-copy/mmap fast path still applies.
+pub fn is_compressed(path: &Path) -> bool {
+    codec_for_extension(path) != Codec::None
+}
+
+/// Sniff the compression codec from the first bytes of `reader` without consuming them
+/// (`fill_buf` only peeks), falling back to `Codec::None` for anything unrecognized.
+fn sniff_codec<R: BufRead>(reader: &mut R) -> Result<Codec, String> {
+    let buf = reader
+        .fill_buf()
+        .map_err(|e| format!("Failed to read file header: {}", e))?;
+    if buf.starts_with(&GZIP_MAGIC) {
+        Ok(Codec::Gzip)
+    } else if buf.starts_with(&LZ4_MAGIC) {
+        Ok(Codec::Lz4)
+    } else {
+        Ok(Codec::None)
+    }
+}
+
+/// A `Write` sink returned by [`writer_for_path`]. Wraps whichever encoder the path's
+/// extension selected; callers must call [`CompressedWriter::finish`] instead of just
+/// dropping it, since a compressed stream's final block/checksum is only written on
+/// explicit finalization.
+pub struct CompressedWriter {
+    inner: WriterInner,
+}
+
+enum WriterInner {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Lz4(lz4_flex::frame::FrameEncoder<BufWriter<File>>),
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            WriterInner::Plain(w) => w.write(buf),
+            WriterInner::Gzip(w) => w.write(buf),
+            WriterInner::Lz4(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.inner {
+            WriterInner::Plain(w) => w.flush(),
+            WriterInner::Gzip(w) => w.flush(),
+            WriterInner::Lz4(w) => w.flush(),
+        }
+    }
+}
+
+impl CompressedWriter {
+    /// Finalize the stream: for `Gzip`/`Lz4` this writes the closing block/frame checksum
+    /// that a plain `flush()` does not, surfacing any error instead of letting it be
+    /// swallowed by the encoder's `Drop` impl. Must be called after the last write instead
+    /// of just letting the writer drop.
+    pub fn finish(self) -> Result<(), String> {
+        match self.inner {
+            WriterInner::Plain(mut w) => {
+                w.flush().map_err(|e| format!("Failed to flush output file: {}", e))
+            }
+            WriterInner::Gzip(w) => w
+                .finish()
+                .map(|_| ())
+                .map_err(|e| format!("Failed to finalize gzip stream: {}", e)),
+            WriterInner::Lz4(w) => w
+                .finish()
+                .map(|_| ())
+                .map_err(|e| format!("Failed to finalize LZ4 stream: {}", e)),
+        }
+    }
+}
+
+/// Open `path` for writing, transparently wrapping it in a streaming gzip or LZ4 encoder
+/// when its extension is `.gz`/`.lz4`; a plain `.csv` (or any other extension) is returned
+/// unwrapped. The result implements `Write`, so callers hand it straight to
+/// `csv::Writer::from_writer`, then call [`CompressedWriter::finish`] (via
+/// `csv::Writer::into_inner`) once all rows are written.
+pub fn writer_for_path(path: &Path) -> Result<CompressedWriter, String> {
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create file {}: {}", path.display(), e))?;
+    let buffered = BufWriter::new(file);
+    let inner = match codec_for_extension(path) {
+        Codec::None => WriterInner::Plain(buffered),
+        Codec::Gzip => WriterInner::Gzip(GzEncoder::new(buffered, Compression::default())),
+        Codec::Lz4 => WriterInner::Lz4(lz4_flex::frame::FrameEncoder::new(buffered)),
+    };
+    Ok(CompressedWriter { inner })
+}
+
+/// Open `path` for reading, transparently decompressing gzip/LZ4 content. The extension is
+/// checked first; if it doesn't name a known codec, the leading bytes are sniffed so a
+/// compressed file under an unexpected extension still loads correctly. Plain CSV content
+/// is returned unwrapped.
+pub fn reader_for_path(path: &Path) -> Result<Box<dyn Read>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open file {}: {}", path.display(), e))?;
+    let mut buffered = BufReader::new(file);
+    let codec = match codec_for_extension(path) {
+        Codec::None => sniff_codec(&mut buffered)?,
+        by_extension => by_extension,
+    };
+    Ok(match codec {
+        Codec::None => Box::new(buffered),
+        Codec::Gzip => Box::new(GzDecoder::new(buffered)),
+        Codec::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(buffered)),
+    })
+}
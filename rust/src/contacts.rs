@@ -1,5 +1,9 @@
+use std::path::Path;
+
 use serde::Deserialize;
 
+use crate::mmap_csv::{self, MMAP_THRESHOLD_BYTES};
+
 /// Contact information from CSV file
 #[derive(Debug, Clone, Deserialize)]
 pub struct Contact {
@@ -13,15 +17,75 @@ pub struct Contact {
 pub fn load_contacts_from_csv(csv_path: &str) -> Result<Vec<Contact>, String> {
     let mut reader = csv::Reader::from_path(csv_path)
         .map_err(|e| format!("Failed to open contacts CSV file {}: {}", csv_path, e))?;
-    
+
     let mut contacts = Vec::new();
-    
+
     for result in reader.deserialize() {
         let contact: Contact = result
             .map_err(|e| format!("Failed to parse contact from CSV: {}", e))?;
         contacts.push(contact);
     }
-    
+
+    Ok(contacts)
+}
+
+/// Load contacts from CSV via a memory-mapped, zero-copy byte-record path.
+///
+/// The file is memory-mapped and iterated as `csv::ByteRecord`s over the mapped slice, with
+/// each field parsed in place so no per-row `String` is allocated. This is the fast path for
+/// the very large contact maps these pipelines produce; prefer [`load_contacts`] to pick it
+/// automatically by size.
+pub fn load_contacts_mmap(csv_path: &str) -> Result<Vec<Contact>, String> {
+    let path = Path::new(csv_path);
+    let mmap = mmap_csv::mmap_csv(path)?;
+
+    let mut reader = csv::ReaderBuilder::new().from_reader(&mmap[..]);
+
+    // Resolve column positions from the header so field order is not assumed.
+    let headers = reader
+        .byte_headers()
+        .map_err(|e| format!("Failed to read contacts CSV headers: {}", e))?;
+    let (mut i_col, mut j_col, mut r_col, mut cluster_col) = (None, None, None, None);
+    for (idx, field) in headers.iter().enumerate() {
+        match field {
+            b"i" => i_col = Some(idx),
+            b"j" => j_col = Some(idx),
+            b"r" => r_col = Some(idx),
+            b"cluster" => cluster_col = Some(idx),
+            _ => {}
+        }
+    }
+    let i_col = i_col.ok_or("Missing 'i' column in contacts CSV")?;
+    let j_col = j_col.ok_or("Missing 'j' column in contacts CSV")?;
+    let r_col = r_col.ok_or("Missing 'r' column in contacts CSV")?;
+    let cluster_col = cluster_col.ok_or("Missing 'cluster' column in contacts CSV")?;
+
+    let mut contacts = Vec::new();
+    let mut record = csv::ByteRecord::new();
+    while reader
+        .read_byte_record(&mut record)
+        .map_err(|e| format!("Failed to read contact byte-record: {}", e))?
+    {
+        let field = |idx: usize| record.get(idx).ok_or("Missing field in contact row");
+        contacts.push(Contact {
+            i: mmap_csv::parse_i32(field(i_col)?)?,
+            j: mmap_csv::parse_i32(field(j_col)?)?,
+            r: mmap_csv::parse_f64(field(r_col)?)?,
+            cluster: mmap_csv::parse_i32(field(cluster_col)?)?,
+        });
+    }
+
     Ok(contacts)
 }
 
+/// Load contacts, selecting the memory-mapped loader for inputs above
+/// [`MMAP_THRESHOLD_BYTES`] and falling back to the plain `csv::Reader` path otherwise.
+pub fn load_contacts(csv_path: &str) -> Result<Vec<Contact>, String> {
+    let path = Path::new(csv_path);
+    if mmap_csv::file_len(path).unwrap_or(0) >= MMAP_THRESHOLD_BYTES {
+        load_contacts_mmap(csv_path)
+    } else {
+        load_contacts_from_csv(csv_path)
+    }
+}
+
@@ -0,0 +1,351 @@
+//! CHARMM/NAMD `DCD` binary trajectory reader.
+//!
+//! DCD is a Fortran "unformatted" stream: every logical record is bracketed by a 4-byte
+//! integer giving its payload length. The file begins with a `CORD` control record, a
+//! title record and an atom-count record, followed by one `(optional unit cell, X, Y, Z)`
+//! block per frame with single-precision coordinates.
+//!
+//! DCD carries no topology, so — as with the reduced CA models these pipelines analyze —
+//! atom `k` (0-based) is taken to be residue `k + 1`'s CA.
+
+use std::path::{Path, PathBuf};
+
+use crate::structure::{Coordinate, FrameData};
+
+use super::TrajectoryFormat;
+
+/// Reader for CHARMM/NAMD DCD files.
+pub struct DcdFormat {
+    file_path: PathBuf,
+}
+
+/// Byte order of a DCD file, detected from its leading record marker.
+#[derive(Clone, Copy)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn i32(self, b: &[u8]) -> i32 {
+        let a = [b[0], b[1], b[2], b[3]];
+        match self {
+            Endian::Little => i32::from_le_bytes(a),
+            Endian::Big => i32::from_be_bytes(a),
+        }
+    }
+
+    fn f32(self, b: &[u8]) -> f32 {
+        let a = [b[0], b[1], b[2], b[3]];
+        match self {
+            Endian::Little => f32::from_le_bytes(a),
+            Endian::Big => f32::from_be_bytes(a),
+        }
+    }
+}
+
+/// Parsed header fields needed to walk the per-frame blocks.
+struct Header {
+    endian: Endian,
+    nframes: usize,
+    natoms: usize,
+    has_unit_cell: bool,
+    /// Byte offset of the first coordinate block.
+    body_offset: usize,
+}
+
+/// Read a 4-byte integer at `pos`, erroring instead of panicking if the file is too short.
+fn read_i32_at(endian: Endian, bytes: &[u8], pos: usize) -> Result<i32, String> {
+    bytes
+        .get(pos..pos + 4)
+        .map(|b| endian.i32(b))
+        .ok_or_else(|| format!("DCD file truncated while reading a record marker at byte {}", pos))
+}
+
+/// Validate a Fortran record at `pos`: its opening length marker must equal `payload_len`,
+/// and — per the DCD record framing — its closing marker, `payload_len` bytes later, must
+/// match it too. Returns the offset just past the closing marker. A mismatched trailing
+/// marker means the file is truncated, corrupt, or was misread with the wrong endianness or
+/// unit-cell flag, so every caller treats it as a hard error rather than guessing.
+fn check_record(endian: Endian, bytes: &[u8], pos: usize, payload_len: usize) -> Result<usize, String> {
+    let open = read_i32_at(endian, bytes, pos)?;
+    if open as usize != payload_len {
+        return Err(format!(
+            "DCD record at byte {} has opening length marker {} (expected {})",
+            pos, open, payload_len
+        ));
+    }
+    let close_pos = pos + 4 + payload_len;
+    let close = read_i32_at(endian, bytes, close_pos)?;
+    if close as usize != payload_len {
+        return Err(format!(
+            "DCD record at byte {} has mismatched trailing length marker {} (expected {}); \
+             file may be truncated, corrupt, or misread with the wrong endianness",
+            pos, close, payload_len
+        ));
+    }
+    Ok(close_pos + 4)
+}
+
+impl DcdFormat {
+    pub fn new(file_path: impl AsRef<Path>) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn read_bytes(&self) -> Result<Vec<u8>, String> {
+        std::fs::read(&self.file_path)
+            .map_err(|e| format!("Failed to read DCD file {}: {}", self.file_path.display(), e))
+    }
+
+    /// Parse the leading control/title/atom-count records and locate the frame body.
+    fn parse_header(bytes: &[u8]) -> Result<Header, String> {
+        if bytes.len() < 4 {
+            return Err("DCD file is too short to contain a header".to_string());
+        }
+
+        // The first record marker must equal 84 in the file's byte order.
+        let endian = if i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == 84 {
+            Endian::Little
+        } else if i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == 84 {
+            Endian::Big
+        } else {
+            return Err("Not a DCD file: leading record marker is not 84".to_string());
+        };
+
+        // Record 1: "CORD" + 20 int32 control words (payload length 84). Its trailing
+        // marker is validated by `check_record` before any control word is trusted.
+        let mut pos = check_record(endian, bytes, 0, 84)?;
+        if &bytes[4..8] != b"CORD" {
+            return Err("Not a DCD file: missing 'CORD' magic".to_string());
+        }
+        let icntrl = |k: usize| endian.i32(&bytes[8 + k * 4..12 + k * 4]);
+        let nframes = icntrl(0).max(0) as usize;
+        let nfixed = icntrl(8);
+        let has_unit_cell = icntrl(10) != 0;
+        if nfixed != 0 {
+            return Err("DCD files with fixed atoms are not supported".to_string());
+        }
+
+        // Record 2: title block.
+        let title_len = read_i32_at(endian, bytes, pos)? as usize;
+        pos = check_record(endian, bytes, pos, title_len)?;
+
+        // Record 3: atom count (payload length 4). The trailing marker is validated before
+        // the atom count itself is read, so a file truncated right after this record's
+        // opening marker errors out instead of reading past the end of `bytes`.
+        let natom_len = read_i32_at(endian, bytes, pos)? as usize;
+        if natom_len != 4 {
+            return Err(format!("Unexpected atom-count record length {}", natom_len));
+        }
+        let close_pos = check_record(endian, bytes, pos, natom_len)?;
+        let natoms = read_i32_at(endian, bytes, pos + 4)?.max(0) as usize;
+        pos = close_pos;
+
+        Ok(Header {
+            endian,
+            nframes,
+            natoms,
+            has_unit_cell,
+            body_offset: pos,
+        })
+    }
+
+    /// Parse every frame into memory.
+    fn parse_frames(&self) -> Result<Vec<(i32, FrameData)>, String> {
+        let bytes = self.read_bytes()?;
+        let header = Self::parse_header(&bytes)?;
+        let Header {
+            endian,
+            nframes,
+            natoms,
+            has_unit_cell,
+            body_offset,
+        } = header;
+
+        // Each coordinate axis record's trailing marker is checked before its values are
+        // trusted, so a truncated file or a wrongly-assumed unit-cell flag fails loudly
+        // here instead of silently misreading later bytes as coordinates.
+        let read_axis = |pos: usize, frame_idx: usize, axis: &str| -> Result<(Vec<f32>, usize), String> {
+            let len = read_i32_at(endian, bytes.as_slice(), pos)? as usize;
+            if len != natoms * 4 {
+                return Err(format!(
+                    "Frame {}: unexpected {} coordinate record length {} (expected {})",
+                    frame_idx + 1,
+                    axis,
+                    len,
+                    natoms * 4
+                ));
+            }
+            let base = pos + 4;
+            if bytes.len() < base + natoms * 4 {
+                return Err(format!(
+                    "DCD file truncated while reading frame {}'s {} coordinate record at byte {}",
+                    frame_idx + 1,
+                    axis,
+                    base
+                ));
+            }
+            let values = (0..natoms)
+                .map(|k| endian.f32(&bytes[base + k * 4..base + k * 4 + 4]))
+                .collect();
+            let next = check_record(endian, &bytes, pos, len).map_err(|e| {
+                format!("Frame {}: {} coordinate record: {}", frame_idx + 1, axis, e)
+            })?;
+            Ok((values, next))
+        };
+
+        let mut frames = Vec::with_capacity(nframes);
+        let mut pos = body_offset;
+
+        for frame_idx in 0..nframes {
+            if has_unit_cell {
+                let cell_len = read_i32_at(endian, &bytes, pos)? as usize;
+                if cell_len != 48 {
+                    return Err(format!(
+                        "Frame {}: unexpected unit-cell record length {} (expected 48)",
+                        frame_idx + 1,
+                        cell_len
+                    ));
+                }
+                pos = check_record(endian, &bytes, pos, cell_len)
+                    .map_err(|e| format!("Frame {}: unit-cell record: {}", frame_idx + 1, e))?;
+            }
+
+            let (xs, next) = read_axis(pos, frame_idx, "X")?;
+            let (ys, next) = read_axis(next, frame_idx, "Y")?;
+            let (zs, next) = read_axis(next, frame_idx, "Z")?;
+            pos = next;
+
+            let mut coords = FrameData::with_capacity(natoms);
+            for k in 0..natoms {
+                coords.insert(
+                    k as i32 + 1,
+                    Coordinate::new(xs[k] as f64, ys[k] as f64, zs[k] as f64),
+                );
+            }
+            frames.push((frame_idx as i32 + 1, coords));
+        }
+
+        Ok(frames)
+    }
+}
+
+impl TrajectoryFormat for DcdFormat {
+    fn frame_count(&self) -> Result<usize, String> {
+        let bytes = self.read_bytes()?;
+        Ok(Self::parse_header(&bytes)?.nframes)
+    }
+
+    fn frames(&self) -> Result<Box<dyn Iterator<Item = Result<(i32, FrameData), String>> + '_>, String> {
+        let frames = self.parse_frames()?;
+        Ok(Box::new(frames.into_iter().map(Ok)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(payload: &[u8]) -> Vec<u8> {
+        let len = payload.len() as i32;
+        let mut out = Vec::with_capacity(payload.len() + 8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(payload);
+        out.extend_from_slice(&len.to_le_bytes());
+        out
+    }
+
+    /// Build a minimal little-endian DCD file with `nframes` frames of `natoms` atoms each,
+    /// no unit cell, each coordinate set to `frame * 100 + axis * 10 + atom` so a transposed
+    /// axis is caught by the test.
+    fn build_dcd(nframes: i32, natoms: i32) -> Vec<u8> {
+        let mut icntrl = [0i32; 20];
+        icntrl[0] = nframes;
+        icntrl[8] = 0; // nfixed
+        icntrl[10] = 0; // no unit cell
+        let mut cord_payload = Vec::new();
+        cord_payload.extend_from_slice(b"CORD");
+        for v in icntrl {
+            cord_payload.extend_from_slice(&v.to_le_bytes());
+        }
+
+        let mut bytes = record(&cord_payload);
+        bytes.extend(record(b"test title"));
+
+        let mut natom_payload = Vec::new();
+        natom_payload.extend_from_slice(&natoms.to_le_bytes());
+        bytes.extend(record(&natom_payload));
+
+        for frame in 0..nframes {
+            for axis in 0..3 {
+                let mut payload = Vec::new();
+                for atom in 0..natoms {
+                    let v = (frame * 100 + axis * 10 + atom) as f32;
+                    payload.extend_from_slice(&v.to_le_bytes());
+                }
+                bytes.extend(record(&payload));
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn parses_header_and_frames() {
+        let bytes = build_dcd(2, 3);
+        let header = DcdFormat::parse_header(&bytes).expect("header should parse");
+        assert_eq!(header.nframes, 2);
+        assert_eq!(header.natoms, 3);
+        assert!(!header.has_unit_cell);
+
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("dcd_test_fixture_{}.dcd", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let format = DcdFormat::new(&path);
+        let frames = format.parse_frames().expect("frames should parse");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(frames.len(), 2);
+        let (frame_num, coords) = &frames[0];
+        assert_eq!(*frame_num, 1);
+        let c = coords.get(&1).expect("atom 1 present");
+        assert_eq!((c.x, c.y, c.z), (0.0, 10.0, 20.0));
+        let (frame_num, coords) = &frames[1];
+        assert_eq!(*frame_num, 2);
+        let c = coords.get(&3).expect("atom 3 present");
+        assert_eq!((c.x, c.y, c.z), (102.0, 112.0, 122.0));
+    }
+
+    #[test]
+    fn rejects_truncated_trailing_marker() {
+        let mut bytes = build_dcd(1, 2);
+        // Corrupt the CORD record's trailing marker so it no longer matches its payload
+        // length; the trailing-marker check must catch this rather than silently
+        // misreading the rest of the file as coordinates.
+        let close_pos = 4 + 84;
+        bytes[close_pos] ^= 0xff;
+        let err = DcdFormat::parse_header(&bytes).unwrap_err();
+        assert!(err.contains("mismatched trailing length marker"), "{err}");
+    }
+
+    #[test]
+    fn truncation_mid_coordinate_payload_errors_instead_of_panicking() {
+        let bytes = build_dcd(1, 3);
+        let body_offset = DcdFormat::parse_header(&bytes).unwrap().body_offset;
+        // Keep the first axis record's opening length marker (which correctly claims
+        // natoms*4 bytes) but supply none of its payload.
+        let mut truncated = bytes;
+        truncated.truncate(body_offset + 4);
+
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("dcd_test_truncated_{}.dcd", std::process::id()));
+        std::fs::write(&path, &truncated).unwrap();
+        let format = DcdFormat::new(&path);
+        let result = format.parse_frames();
+        std::fs::remove_file(&path).ok();
+
+        let err = result.expect_err("truncated payload must error, not panic");
+        assert!(err.contains("truncated"), "{err}");
+    }
+}
@@ -0,0 +1,213 @@
+//! GROMACS `GRO` text-format trajectory reader.
+//!
+//! A `.gro` trajectory is a sequence of concatenated frames, each laid out as: a title
+//! line, an atom-count line, one fixed-width record per atom (residue number, residue
+//! name, atom name, atom number, `x y z` in nm, optional velocities), and a trailing
+//! box-vector line. Coordinates are in nanometres, so — consistent with the other
+//! binary/text readers — atom records are filtered down to the CA atom of each residue
+//! and scaled to Ångströms.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::{Path, PathBuf};
+
+use crate::structure::{Coordinate, FrameData};
+
+use super::TrajectoryFormat;
+
+/// Nanometre → Ångström conversion for coordinates.
+const NM_TO_ANGSTROM: f64 = 10.0;
+
+/// Reader for GROMACS GRO files.
+pub struct GroFormat {
+    file_path: PathBuf,
+}
+
+impl GroFormat {
+    pub fn new(file_path: impl AsRef<Path>) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn open(&self) -> Result<Lines<BufReader<File>>, String> {
+        let file = File::open(&self.file_path)
+            .map_err(|e| format!("Failed to open GRO file {}: {}", self.file_path.display(), e))?;
+        Ok(BufReader::new(file).lines())
+    }
+}
+
+impl TrajectoryFormat for GroFormat {
+    fn frame_count(&self) -> Result<usize, String> {
+        let mut lines = self.open()?;
+        let mut count = 0;
+        while lines.next().is_some() {
+            let natoms_line = match lines.next() {
+                Some(line) => line.map_err(|e| format!("Error reading line: {}", e))?,
+                None => return Err("Truncated GRO file: missing atom-count line".to_string()),
+            };
+            let natoms = natoms_line
+                .trim()
+                .parse::<usize>()
+                .map_err(|e| format!("Invalid atom count '{}': {}", natoms_line, e))?;
+            for _ in 0..natoms {
+                if lines.next().is_none() {
+                    return Err("Truncated GRO file: missing atom record".to_string());
+                }
+            }
+            // Box-vector line.
+            if lines.next().is_none() {
+                return Err("Truncated GRO file: missing box-vector line".to_string());
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn frames(&self) -> Result<Box<dyn Iterator<Item = Result<(i32, FrameData), String>> + '_>, String> {
+        Ok(Box::new(GroFrames {
+            lines: self.open()?,
+            frame_idx: 0,
+        }))
+    }
+}
+
+/// Streaming iterator over GRO frames, parsing one title/atoms/box block per step.
+struct GroFrames {
+    lines: Lines<BufReader<File>>,
+    frame_idx: i32,
+}
+
+impl GroFrames {
+    fn parse_next(&mut self) -> Result<Option<(i32, FrameData)>, String> {
+        // Title line; its absence cleanly marks end-of-file.
+        if self.lines.next().is_none() {
+            return Ok(None);
+        }
+
+        let natoms_line = self
+            .lines
+            .next()
+            .ok_or("Truncated GRO file: missing atom-count line")?
+            .map_err(|e| format!("Error reading line: {}", e))?;
+        let natoms = natoms_line
+            .trim()
+            .parse::<usize>()
+            .map_err(|e| format!("Invalid atom count '{}': {}", natoms_line, e))?;
+
+        let mut coords = FrameData::with_capacity(natoms);
+        for _ in 0..natoms {
+            let line = self
+                .lines
+                .next()
+                .ok_or("Truncated GRO file: missing atom record")?
+                .map_err(|e| format!("Error reading line: {}", e))?;
+            if line.len() < 44 {
+                continue;
+            }
+            // Fixed GRO columns: 0-5 residue number, 10-15 atom name, 20-28/28-36/36-44 x/y/z (nm).
+            let residue_str = line.get(0..5).unwrap_or("").trim();
+            let residue_num = match residue_str.parse::<i32>() {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            let atom_name = line.get(10..15).unwrap_or("").trim();
+            if atom_name != "CA" {
+                continue;
+            }
+            let x = match line.get(20..28).unwrap_or("").trim().parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let y = match line.get(28..36).unwrap_or("").trim().parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let z = match line.get(36..44).unwrap_or("").trim().parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            coords.insert(
+                residue_num,
+                Coordinate::new(
+                    x * NM_TO_ANGSTROM,
+                    y * NM_TO_ANGSTROM,
+                    z * NM_TO_ANGSTROM,
+                ),
+            );
+        }
+
+        // Box-vector line.
+        self.lines
+            .next()
+            .ok_or("Truncated GRO file: missing box-vector line")?
+            .map_err(|e| format!("Error reading line: {}", e))?;
+
+        self.frame_idx += 1;
+        Ok(Some((self.frame_idx, coords)))
+    }
+}
+
+impl Iterator for GroFrames {
+    type Item = Result<(i32, FrameData), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom_line(residue_num: i32, atom_name: &str, atom_num: i32, x: f64, y: f64, z: f64) -> String {
+        format!(
+            "{:>5}{:<5}{:<5}{:>5}{:8.3}{:8.3}{:8.3}",
+            residue_num, "ALA", atom_name, atom_num, x, y, z
+        )
+    }
+
+    fn build_gro(frames: &[&[(i32, &str, f64, f64, f64)]]) -> String {
+        let mut out = String::new();
+        for (frame_idx, atoms) in frames.iter().enumerate() {
+            out.push_str(&format!("test frame {}\n", frame_idx));
+            out.push_str(&format!("{}\n", atoms.len()));
+            for (i, &(residue_num, atom_name, x, y, z)) in atoms.iter().enumerate() {
+                out.push_str(&atom_line(residue_num, atom_name, i as i32 + 1, x, y, z));
+                out.push('\n');
+            }
+            out.push_str("   1.00000   1.00000   1.00000\n");
+        }
+        out
+    }
+
+    #[test]
+    fn parses_frames_filtering_to_ca_and_scaling_to_angstrom() {
+        let contents = build_gro(&[
+            &[(1, "CA", 1.0, 2.0, 3.0), (1, "N", 9.0, 9.0, 9.0)],
+            &[(1, "CA", 1.5, 2.5, 3.5)],
+        ]);
+
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("gro_test_fixture_{}.gro", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let format = GroFormat::new(&path);
+
+        assert_eq!(format.frame_count().unwrap(), 2);
+
+        let frames: Vec<(i32, FrameData)> = format
+            .frames()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(frames.len(), 2);
+        let (frame_num, coords) = &frames[0];
+        assert_eq!(*frame_num, 1);
+        // Only the CA atom survives the filter.
+        assert_eq!(coords.len(), 1);
+        let c = coords.get(&1).expect("residue 1 CA present");
+        assert_eq!((c.x, c.y, c.z), (10.0, 20.0, 30.0));
+    }
+}
@@ -0,0 +1,71 @@
+//! Pluggable trajectory-format subsystem.
+//!
+//! Historically the pipeline only understood multi-model PDB text, so the binary MD
+//! formats that dominate production (CHARMM/NAMD `DCD`, GROMACS `XTC`) had to be converted
+//! first. This module reorganizes frame reading by file format behind the
+//! [`TrajectoryFormat`] trait: each concrete reader knows how to report its
+//! [`frame_count`](TrajectoryFormat::frame_count) and to stream frames as
+//! `(frame_number, FrameData)` pairs, and [`open_trajectory`] picks the reader by file
+//! extension so the rest of the contact/Q pipeline is format-agnostic.
+
+use std::path::Path;
+
+use crate::structure::FrameData;
+
+pub mod dcd;
+pub mod gro;
+pub mod pdb;
+pub mod xtc;
+
+pub use dcd::DcdFormat;
+pub use gro::GroFormat;
+pub use pdb::PdbFormat;
+pub use xtc::XtcFormat;
+
+/// A reader for one trajectory file format.
+///
+/// [`frames`](Self::frames) returns a fresh iterator each call so a trajectory can be
+/// walked more than once (e.g. to count then process). The PDB and GRO readers stream
+/// frames lazily; the DCD and XTC readers read the whole file and parse every frame into
+/// memory before yielding the first one.
+pub trait TrajectoryFormat {
+    /// Number of frames the file contains.
+    fn frame_count(&self) -> Result<usize, String>;
+
+    /// Stream the frames in file order, each as `(frame_number, FrameData)` where
+    /// `FrameData` maps residue number to its CA coordinate.
+    fn frames(&self) -> Result<Box<dyn Iterator<Item = Result<(i32, FrameData), String>> + '_>, String>;
+}
+
+/// Human-readable name of the format [`open_trajectory`] would select for `path`, for
+/// status/logging output.
+pub fn format_name(path: impl AsRef<Path>) -> &'static str {
+    match extension_of(path.as_ref()).as_str() {
+        "dcd" => "DCD",
+        "xtc" => "XTC",
+        "gro" => "GRO",
+        _ => "PDB",
+    }
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Pick a [`TrajectoryFormat`] reader for `path` by its file extension.
+///
+/// Recognizes `.pdb`/`.ent` (PDB text), `.dcd` (CHARMM/NAMD), `.xtc` and `.gro` (GROMACS);
+/// anything else falls back to the PDB reader, matching the historical default.
+pub fn open_trajectory(path: impl AsRef<Path>) -> Result<Box<dyn TrajectoryFormat>, String> {
+    let path = path.as_ref();
+    match extension_of(path).as_str() {
+        "dcd" => Ok(Box::new(DcdFormat::new(path))),
+        "xtc" => Ok(Box::new(XtcFormat::new(path))),
+        "gro" => Ok(Box::new(GroFormat::new(path))),
+        // `.pdb`/`.ent` and any unrecognized extension fall back to the PDB text reader.
+        _ => Ok(Box::new(PdbFormat::new(path))),
+    }
+}
@@ -0,0 +1,183 @@
+//! PDB text-format trajectory reader.
+//!
+//! Parses the `MODEL`/`ATOM`/`ENDMDL` layout, extracting the CA atom of each residue per
+//! model. This is the original behavior of `PdbTrajectory::read_pdb`, now expressed as a
+//! streaming [`TrajectoryFormat`] so it shares the format-agnostic pipeline with the
+//! binary readers.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::{Path, PathBuf};
+
+use crate::structure::{Coordinate, FrameData};
+
+use super::TrajectoryFormat;
+
+/// Reader for multi-model PDB text files.
+pub struct PdbFormat {
+    file_path: PathBuf,
+}
+
+impl PdbFormat {
+    pub fn new(file_path: impl AsRef<Path>) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn open(&self) -> Result<Lines<BufReader<File>>, String> {
+        let file = File::open(&self.file_path)
+            .map_err(|e| format!("Failed to open PDB file {}: {}", self.file_path.display(), e))?;
+        Ok(BufReader::new(file).lines())
+    }
+}
+
+impl TrajectoryFormat for PdbFormat {
+    fn frame_count(&self) -> Result<usize, String> {
+        // Count models without materializing coordinates.
+        let mut count = 0;
+        let mut model_found = false;
+        let mut saw_atoms = false;
+        for line in self.open()? {
+            let line = line.map_err(|e| format!("Error reading line: {}", e))?;
+            if line.starts_with("ENDMDL") {
+                count += 1;
+            } else if line.starts_with("MODEL") {
+                model_found = true;
+            } else if line.starts_with("ATOM") {
+                saw_atoms = true;
+            }
+        }
+        // Single-model files without MODEL/ENDMDL markers count as one frame.
+        if count == 0 && !model_found && saw_atoms {
+            count = 1;
+        }
+        Ok(count)
+    }
+
+    fn frames(&self) -> Result<Box<dyn Iterator<Item = Result<(i32, FrameData), String>> + '_>, String> {
+        Ok(Box::new(PdbFrames {
+            lines: self.open()?,
+            current_model: None,
+            current_coords: FrameData::new(),
+            model_found: false,
+            model_saved: false,
+            done: false,
+        }))
+    }
+}
+
+/// Streaming iterator over PDB models, emitting each frame as its `ENDMDL` (or the next
+/// `MODEL`) is reached.
+struct PdbFrames {
+    lines: Lines<BufReader<File>>,
+    current_model: Option<i32>,
+    current_coords: FrameData,
+    model_found: bool,
+    model_saved: bool,
+    done: bool,
+}
+
+impl Iterator for PdbFrames {
+    type Item = Result<(i32, FrameData), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(format!("Error reading line: {}", e)));
+                }
+                None => {
+                    // End of file: flush a pending, unsaved model, then the single-model
+                    // case where no MODEL/ENDMDL markers were ever seen.
+                    self.done = true;
+                    if !self.model_saved {
+                        if let Some(model_num) = self.current_model.take() {
+                            if !self.current_coords.is_empty() {
+                                let coords = std::mem::take(&mut self.current_coords);
+                                return Some(Ok((model_num, coords)));
+                            }
+                        } else if !self.model_found && !self.current_coords.is_empty() {
+                            let coords = std::mem::take(&mut self.current_coords);
+                            return Some(Ok((1, coords)));
+                        }
+                    }
+                    return None;
+                }
+            };
+
+            if line.starts_with("MODEL") {
+                self.model_found = true;
+
+                // Emit the previous model if it hasn't been flushed at its ENDMDL.
+                let pending = if !self.model_saved {
+                    self.current_model.take()
+                } else {
+                    None
+                };
+
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() < 2 {
+                    self.done = true;
+                    return Some(Err(format!("Invalid MODEL line: {}", line)));
+                }
+                let model_num = match parts[1].parse::<i32>() {
+                    Ok(n) => n,
+                    Err(e) => {
+                        self.done = true;
+                        return Some(Err(format!("Failed to parse model number: {}", e)));
+                    }
+                };
+
+                if let Some(prev_model) = pending {
+                    let coords = std::mem::replace(&mut self.current_coords, FrameData::new());
+                    self.current_model = Some(model_num);
+                    self.model_saved = false;
+                    return Some(Ok((prev_model, coords)));
+                }
+
+                self.current_model = Some(model_num);
+                self.current_coords = FrameData::new();
+                self.model_saved = false;
+            } else if line.starts_with("ATOM") && line.contains(" CA ") {
+                // PDB fixed columns: 22-26 residue number, 30-38 x, 38-46 y, 46-54 z.
+                if line.len() < 54 {
+                    continue;
+                }
+                let residue_str = line.get(22..26).unwrap_or("").trim();
+                let residue_num = match residue_str.parse::<i32>() {
+                    Ok(n) => n,
+                    Err(_) => continue,
+                };
+                let x = match line.get(30..38).unwrap_or("").trim().parse::<f64>() {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                let y = match line.get(38..46).unwrap_or("").trim().parse::<f64>() {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                let z = match line.get(46..54).unwrap_or("").trim().parse::<f64>() {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                self.current_coords
+                    .insert(residue_num, Coordinate::new(x, y, z));
+            } else if line.starts_with("ENDMDL") {
+                if let Some(model_num) = self.current_model {
+                    if !self.model_saved {
+                        self.model_saved = true;
+                        let coords = std::mem::replace(&mut self.current_coords, FrameData::new());
+                        return Some(Ok((model_num, coords)));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,28 @@
+pub mod bin_io;
+pub mod cache;
+pub mod compressed_io;
 pub mod contacts;
+pub mod formats;
+pub mod mmap_csv;
+pub mod parquet_io;
 pub mod structure;
 pub mod trajectory;
+pub mod tsne;
 
 #[cfg(feature = "python")]
 pub mod python_bindings;
 
 // Re-export commonly used types and traits
-pub use contacts::{Contact, load_contacts_from_csv};
+pub use bin_io::{is_bin_format, load_results_from_bin, save_results_to_bin};
+pub use contacts::{load_contacts, load_contacts_from_csv, load_contacts_mmap, Contact};
+pub use formats::{format_name, open_trajectory, TrajectoryFormat};
+pub use parquet_io::{load_results_from_parquet, OutputFormat};
 pub use structure::{Coordinate, FrameData};
-pub use trajectory::{FrameResult, PdbTrajectory, SmoothedResult, Trajectory, TrajectoryData, WindowSummary};
+pub use trajectory::{
+    batch_process_replicas, consensus_formation_order, load_results_from_csv,
+    pathway_statistics, save_batch_aggregate_to_csv, select_frame_range, BatchOperator,
+    ConsensusFormation, FormationSpread, FrameResult, Hysteresis, MeanClusterFillingAcc,
+    MeanClusterFillingOperator, PathwayFrequency, PathwayStatistics, PdbTrajectory, RankSpread,
+    SmoothedResult, Trajectory, TrajectoryData, WindowSummary,
+};
+pub use tsne::{tsne_embedding, TsneParams, TsnePoint};
@@ -0,0 +1,49 @@
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/// Size threshold (bytes) above which callers prefer the memory-mapped loader over the
+/// default `csv::Reader`. Large contact maps and parsed-result CSVs benefit from the
+/// zero-copy byte-record path; small files don't justify the mmap setup cost.
+pub const MMAP_THRESHOLD_BYTES: u64 = 8 * 1024 * 1024; // 8 MiB
+
+/// Memory-map a CSV file and return the mapping, so callers can build a `csv::Reader`
+/// over `&mmap[..]` and read rows as `csv::ByteRecord`s without copying the file into an
+/// owned buffer first.
+///
+/// The returned `Mmap` must be kept alive for as long as a reader borrows it.
+pub fn mmap_csv(path: &Path) -> Result<Mmap, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open file {} for mmap: {}", path.display(), e))?;
+    // SAFETY: the file is opened read-only and the mapping lives no longer than the
+    // returned `Mmap`; callers treat the mapped bytes as an immutable slice.
+    let mmap = unsafe { Mmap::map(&file) }
+        .map_err(|e| format!("Failed to memory-map file {}: {}", path.display(), e))?;
+    Ok(mmap)
+}
+
+/// Return the size of a file in bytes, used to decide whether the mmap path is worth it.
+pub fn file_len(path: &Path) -> Result<u64, String> {
+    std::fs::metadata(path)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to stat file {}: {}", path.display(), e))
+}
+
+/// Parse an ASCII byte slice into an `i32` without allocating an intermediate `String`.
+pub fn parse_i32(bytes: &[u8]) -> Result<i32, String> {
+    std::str::from_utf8(bytes)
+        .map_err(|e| format!("Invalid UTF-8 in integer field: {}", e))?
+        .trim()
+        .parse::<i32>()
+        .map_err(|e| format!("Failed to parse integer field: {}", e))
+}
+
+/// Parse an ASCII byte slice into an `f64` without allocating an intermediate `String`.
+pub fn parse_f64(bytes: &[u8]) -> Result<f64, String> {
+    std::str::from_utf8(bytes)
+        .map_err(|e| format!("Invalid UTF-8 in float field: {}", e))?
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("Failed to parse float field: {}", e))
+}
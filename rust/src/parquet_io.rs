@@ -0,0 +1,410 @@
+//! Columnar Arrow-backed Parquet I/O for per-frame results and summaries.
+//!
+//! The `save_*_to_csv` helpers in [`crate::trajectory`] emit plain text, which bloats for
+//! long trajectories with many clusters and is slow to reload. This module provides a
+//! typed, compressed, column-pruned alternative: each record type is written as an Arrow
+//! [`RecordBatch`] to Parquet with a `frame` column, the scalar columns (`q`, `contacts`,
+//! …) and one `Float64` column per cluster. [`load_results_from_parquet`] mirrors
+//! [`crate::trajectory::load_results_from_csv`] for the `results: None` reload path.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Float64Array, Int32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+
+use crate::trajectory::{FrameResult, SmoothedResult, WindowSummary};
+
+/// Output backend for the saved analysis files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    Parquet,
+    /// [`crate::bin_io`]'s compact hand-rolled binary layout.
+    Bin,
+}
+
+impl OutputFormat {
+    /// File extension (without the dot) that the format writes.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Parquet => "parquet",
+            OutputFormat::Bin => "bin",
+        }
+    }
+}
+
+/// Sorted union of every cluster number appearing in a set of cluster maps.
+fn cluster_numbers<'a>(maps: impl Iterator<Item = &'a HashMap<i32, f64>>) -> Vec<i32> {
+    let mut set: std::collections::HashSet<i32> = std::collections::HashSet::new();
+    for map in maps {
+        set.extend(map.keys().copied());
+    }
+    let mut numbers: Vec<i32> = set.into_iter().collect();
+    numbers.sort();
+    numbers
+}
+
+/// Write a `RecordBatch` to `path` as Parquet with Snappy compression.
+fn write_batch(batch: RecordBatch, path: &Path) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create Parquet file {}: {}", path.display(), e))?;
+    let props = parquet::file::properties::WriterProperties::builder()
+        .set_compression(parquet::basic::Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))
+        .map_err(|e| format!("Failed to open Parquet writer: {}", e))?;
+    writer
+        .write(&batch)
+        .map_err(|e| format!("Failed to write Parquet batch: {}", e))?;
+    writer
+        .close()
+        .map_err(|e| format!("Failed to finalize Parquet file: {}", e))?;
+    Ok(())
+}
+
+/// Save per-frame results as Parquet: `frame`, `contacts`, `q`, `q_soft` and one
+/// `cluster_{n}` float column per cluster.
+pub fn save_results_to_parquet(results: &[FrameResult], path: &Path) -> Result<(), String> {
+    let clusters = cluster_numbers(results.iter().map(|r| &r.clusters_filling));
+
+    let mut fields = vec![
+        Field::new("frame", DataType::Int32, false),
+        Field::new("contacts", DataType::UInt64, false),
+        Field::new("q", DataType::Float64, false),
+        Field::new("q_soft", DataType::Float64, false),
+    ];
+    for cluster in &clusters {
+        fields.push(Field::new(
+            format!("cluster_{}", cluster),
+            DataType::Float64,
+            false,
+        ));
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(results.iter().map(|r| r.frame).collect::<Vec<_>>())),
+        Arc::new(UInt64Array::from(
+            results.iter().map(|r| r.contacts as u64).collect::<Vec<_>>(),
+        )),
+        Arc::new(Float64Array::from(results.iter().map(|r| r.q).collect::<Vec<_>>())),
+        Arc::new(Float64Array::from(
+            results.iter().map(|r| r.q_soft).collect::<Vec<_>>(),
+        )),
+    ];
+    for cluster in &clusters {
+        columns.push(Arc::new(Float64Array::from(
+            results
+                .iter()
+                .map(|r| r.clusters_filling.get(cluster).copied().unwrap_or(0.0))
+                .collect::<Vec<_>>(),
+        )));
+    }
+
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| format!("Failed to build Parquet record batch: {}", e))?;
+    write_batch(batch, path)
+}
+
+/// Save window summaries as Parquet: `frame` and one `cluster_{n}` float column per
+/// cluster. This is the binary columnar cache that replaces the misnamed
+/// `_summary_binary.csv` — [`crate::trajectory::determine_formation_order`] (via
+/// [`load_summary_from_parquet`]) reads it back as typed columns instead of parsing
+/// stringified `{cluster: fraction}` maps. Schema metadata records the cluster ids and, if
+/// the summary was binarized, the `cutoff` used, so the file is self-describing about how
+/// it was produced.
+pub fn save_summary_to_parquet(
+    summaries: &[WindowSummary],
+    clusters: &[i32],
+    path: &Path,
+    cutoff: Option<f64>,
+) -> Result<(), String> {
+    // When bootstrap CIs were computed, append a low/high column per cluster.
+    let has_ci = summaries.first().is_some_and(|s| s.cluster_ci.is_some());
+
+    let mut fields = vec![Field::new("frame", DataType::Int32, false)];
+    for cluster in clusters {
+        fields.push(Field::new(
+            format!("cluster_{}", cluster),
+            DataType::Float64,
+            false,
+        ));
+    }
+    if has_ci {
+        for cluster in clusters {
+            fields.push(Field::new(
+                format!("cluster_{}_ci_low", cluster),
+                DataType::Float64,
+                false,
+            ));
+            fields.push(Field::new(
+                format!("cluster_{}_ci_high", cluster),
+                DataType::Float64,
+                false,
+            ));
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(Int32Array::from(
+        summaries.iter().map(|s| s.frame).collect::<Vec<_>>(),
+    ))];
+    for cluster in clusters {
+        columns.push(Arc::new(Float64Array::from(
+            summaries
+                .iter()
+                .map(|s| s.cluster_means.get(cluster).copied().unwrap_or(0.0))
+                .collect::<Vec<_>>(),
+        )));
+    }
+    if has_ci {
+        for cluster in clusters {
+            columns.push(Arc::new(Float64Array::from(
+                summaries
+                    .iter()
+                    .map(|s| ci_bound(s, cluster).0)
+                    .collect::<Vec<_>>(),
+            )));
+            columns.push(Arc::new(Float64Array::from(
+                summaries
+                    .iter()
+                    .map(|s| ci_bound(s, cluster).1)
+                    .collect::<Vec<_>>(),
+            )));
+        }
+    }
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "cluster_ids".to_string(),
+        clusters
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    if let Some(cutoff) = cutoff {
+        metadata.insert("cutoff".to_string(), cutoff.to_string());
+    }
+    let schema = Schema::new(fields).with_metadata(metadata);
+
+    let batch = RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| format!("Failed to build Parquet record batch: {}", e))?;
+    write_batch(batch, path)
+}
+
+/// Load window summaries from a Parquet file written by [`save_summary_to_parquet`].
+///
+/// Discovers the per-cluster (and, if present, bootstrap CI) columns directly from the
+/// schema by name — the `cluster_ids`/`cutoff` schema metadata is descriptive only and not
+/// required to load — so this is a typed columnar read that skips the brittle
+/// `{cluster: fraction}` brace/colon string parsing the CSV summary path requires.
+pub fn load_summary_from_parquet(path: &Path) -> Result<Vec<WindowSummary>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open Parquet file {}: {}", path.display(), e))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| format!("Failed to open Parquet reader: {}", e))?;
+    let reader = builder
+        .build()
+        .map_err(|e| format!("Failed to build Parquet reader: {}", e))?;
+
+    let mut summaries = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| format!("Failed to read Parquet batch: {}", e))?;
+        let schema = batch.schema();
+
+        let frame = column_i32(&batch, "frame")?;
+
+        let mean_cols: Vec<(i32, Float64Array)> = schema
+            .fields()
+            .iter()
+            .filter_map(|field| {
+                let name = field.name();
+                let num = name
+                    .strip_prefix("cluster_")?
+                    .parse::<i32>()
+                    .ok()?;
+                let array = batch
+                    .column_by_name(name)?
+                    .as_any()
+                    .downcast_ref::<Float64Array>()?
+                    .clone();
+                Some((num, array))
+            })
+            .collect();
+
+        // Precompute the CI column pairs per cluster (if any) once per batch, rather than
+        // re-resolving them by name on every row.
+        let ci_cols: Vec<(i32, Float64Array, Float64Array)> = mean_cols
+            .iter()
+            .filter_map(|(num, _)| {
+                let low = column_f64(&batch, &format!("cluster_{}_ci_low", num)).ok()?;
+                let high = column_f64(&batch, &format!("cluster_{}_ci_high", num)).ok()?;
+                Some((*num, low, high))
+            })
+            .collect();
+        let has_ci = !ci_cols.is_empty();
+
+        for row in 0..batch.num_rows() {
+            let mut cluster_means = HashMap::new();
+            for (num, array) in &mean_cols {
+                cluster_means.insert(*num, array.value(row));
+            }
+
+            let cluster_ci = if has_ci {
+                let mut ci = HashMap::new();
+                for (num, low, high) in &ci_cols {
+                    ci.insert(*num, (low.value(row), high.value(row)));
+                }
+                Some(ci)
+            } else {
+                None
+            };
+
+            summaries.push(WindowSummary {
+                frame: frame.value(row),
+                cluster_means,
+                cluster_ci,
+            });
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// Bootstrap CI bounds for one cluster of a summary, defaulting to `(0.0, 0.0)`.
+fn ci_bound(summary: &WindowSummary, cluster: &i32) -> (f64, f64) {
+    summary
+        .cluster_ci
+        .as_ref()
+        .and_then(|ci| ci.get(cluster).copied())
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Save smoothed results as Parquet: `frame`, `q_smooth` and one `cluster_{n}_smooth`
+/// float column per cluster.
+pub fn save_smoothed_to_parquet(
+    smoothed: &[SmoothedResult],
+    clusters: &[i32],
+    path: &Path,
+) -> Result<(), String> {
+    let mut fields = vec![
+        Field::new("frame", DataType::Int32, false),
+        Field::new("q_smooth", DataType::Float64, false),
+    ];
+    for cluster in clusters {
+        fields.push(Field::new(
+            format!("cluster_{}_smooth", cluster),
+            DataType::Float64,
+            false,
+        ));
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(
+            smoothed.iter().map(|s| s.frame).collect::<Vec<_>>(),
+        )),
+        Arc::new(Float64Array::from(
+            smoothed.iter().map(|s| s.q_smooth).collect::<Vec<_>>(),
+        )),
+    ];
+    for cluster in clusters {
+        columns.push(Arc::new(Float64Array::from(
+            smoothed
+                .iter()
+                .map(|s| s.cluster_smooth.get(cluster).copied().unwrap_or(0.0))
+                .collect::<Vec<_>>(),
+        )));
+    }
+
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| format!("Failed to build Parquet record batch: {}", e))?;
+    write_batch(batch, path)
+}
+
+/// Load per-frame results from a Parquet file written by [`save_results_to_parquet`].
+///
+/// Reconstructs each [`FrameResult`] from the `frame`/`contacts`/`q`/`q_soft` columns and
+/// the per-cluster columns; `contact_list` is left empty (as with the CSV summary path)
+/// and `clusters_filling_soft` mirrors `clusters_filling`.
+pub fn load_results_from_parquet(path: &Path) -> Result<Vec<FrameResult>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open Parquet file {}: {}", path.display(), e))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| format!("Failed to open Parquet reader: {}", e))?;
+    let reader = builder
+        .build()
+        .map_err(|e| format!("Failed to build Parquet reader: {}", e))?;
+
+    let mut results = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| format!("Failed to read Parquet batch: {}", e))?;
+        let schema = batch.schema();
+
+        let frame = column_i32(&batch, "frame")?;
+        let contacts = column_u64(&batch, "contacts").ok();
+        let q = column_f64(&batch, "q")?;
+        let q_soft = column_f64(&batch, "q_soft").ok();
+
+        // Discover the per-cluster columns from the schema.
+        let cluster_cols: Vec<(i32, Float64Array)> = schema
+            .fields()
+            .iter()
+            .filter_map(|field| {
+                let name = field.name();
+                let num = name.strip_prefix("cluster_")?.parse::<i32>().ok()?;
+                let array = batch
+                    .column_by_name(name)?
+                    .as_any()
+                    .downcast_ref::<Float64Array>()?
+                    .clone();
+                Some((num, array))
+            })
+            .collect();
+
+        for row in 0..batch.num_rows() {
+            let mut clusters_filling = HashMap::new();
+            for (num, array) in &cluster_cols {
+                clusters_filling.insert(*num, array.value(row));
+            }
+            let q_value = q.value(row);
+            results.push(FrameResult {
+                frame: frame.value(row),
+                contacts: contacts.as_ref().map(|c| c.value(row) as usize).unwrap_or(0),
+                q: q_value,
+                q_soft: q_soft.as_ref().map(|c| c.value(row)).unwrap_or(q_value),
+                contact_list: Vec::new(),
+                clusters_filling_soft: clusters_filling.clone(),
+                clusters_filling,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+fn column_i32(batch: &RecordBatch, name: &str) -> Result<Int32Array, String> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<Int32Array>().cloned())
+        .ok_or_else(|| format!("Missing or mistyped '{}' column in Parquet file", name))
+}
+
+fn column_f64(batch: &RecordBatch, name: &str) -> Result<Float64Array, String> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<Float64Array>().cloned())
+        .ok_or_else(|| format!("Missing or mistyped '{}' column in Parquet file", name))
+}
+
+fn column_u64(batch: &RecordBatch, name: &str) -> Result<UInt64Array, String> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<UInt64Array>().cloned())
+        .ok_or_else(|| format!("Missing or mistyped '{}' column in Parquet file", name))
+}
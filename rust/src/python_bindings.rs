@@ -2,27 +2,54 @@ use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList, PyTuple};
 use std::path::{Path, PathBuf};
 
-use crate::contacts::load_contacts_from_csv;
-use crate::trajectory::{PdbTrajectory, Trajectory};
+use crate::contacts::{load_contacts, Contact};
+use crate::parquet_io::OutputFormat;
+use crate::trajectory::{
+    determine_formation_order, FrameResult, Hysteresis, PdbTrajectory, Trajectory, WindowSummary,
+};
+use crate::tsne::{tsne_embedding, TsneParams};
+
+/// Map the Python `output_format` string ("csv" or "parquet") onto [`OutputFormat`].
+fn parse_output_format(name: &str) -> PyResult<OutputFormat> {
+    match name.to_ascii_lowercase().as_str() {
+        "csv" => Ok(OutputFormat::Csv),
+        "parquet" => Ok(OutputFormat::Parquet),
+        other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown output_format '{}' (expected 'csv' or 'parquet')",
+            other
+        ))),
+    }
+}
 
 /// Python binding for read_trajectory function
 #[pyfunction]
-#[pyo3(signature = (trajectory_file, contacts_file, cutoff_distance=1.2, max_frames=None, output_csv=None))]
+#[pyo3(signature = (trajectory_file, contacts_file, cutoff_distance=1.2, beta=5.0, lambda=1.2, max_frames=None, sample_frames=None, seed=None, output_csv=None, num_workers=None, progress_callback=None, use_cache=true, output_format="csv"))]
+#[allow(clippy::too_many_arguments)]
 fn read_trajectory(
     py: Python<'_>,
     trajectory_file: &str,
     contacts_file: &str,
     cutoff_distance: f64,
+    beta: f64,
+    lambda: f64,
     max_frames: Option<usize>,
+    sample_frames: Option<usize>,
+    seed: Option<u64>,
     output_csv: Option<&str>,
+    num_workers: Option<usize>,
+    progress_callback: Option<PyObject>,
+    use_cache: bool,
+    output_format: &str,
 ) -> PyResult<PyObject> {
-    // Load contacts
-    let contacts = load_contacts_from_csv(contacts_file)
+    let output_format = parse_output_format(output_format)?;
+
+    // Load contacts (memory-mapped zero-copy path for large contact maps)
+    let contacts = load_contacts(contacts_file)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to load contacts: {}", e)))?;
-    
+
     // Create trajectory reader
     let trajectory = PdbTrajectory::new(trajectory_file);
-    
+
     // Determine output path - auto-generate if None
     let output_path = if let Some(csv_path) = output_csv {
         Some(PathBuf::from(csv_path))
@@ -34,18 +61,49 @@ fn read_trajectory(
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("trajectory");
-        let output_file = format!("{}_parsed.csv", base_name);
+        let output_file = format!("{}_parsed.{}", base_name, output_format.extension());
         Some(traj_dir.join(output_file))
     };
     
-    // Read trajectory
-    let results = trajectory.read_trajectory(
-        &contacts,
-        cutoff_distance,
-        max_frames,
-        output_path.as_deref(),
-    )
+    // Read trajectory. Fan frame-level work out to a worker pool (0 = all cores) and
+    // invoke the optional Python progress callback periodically with
+    // (frames_done, total_frames). The callback is invoked while the GIL is held and
+    // skipped entirely when None.
+    let progress_every = 1000usize;
+    let results = py.allow_threads(|| {
+        trajectory.read_trajectory_pooled(
+            &contacts,
+            cutoff_distance,
+            beta,
+            lambda,
+            max_frames,
+            sample_frames,
+            seed,
+            num_workers.unwrap_or(0),
+            output_path.as_deref(),
+            output_format,
+            progress_every,
+            |done, total| {
+                if let Some(cb) = progress_callback.as_ref() {
+                    Python::with_gil(|py| {
+                        let args = PyTuple::new_bound(py, &[done.into_py(py), total.into_py(py)]);
+                        let _ = cb.call1(py, args);
+                    });
+                }
+            },
+        )
+    })
     .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to read trajectory: {}", e)))?;
+
+    // Write a bincode cache sidecar keyed by the trajectory bytes, cutoff and contacts
+    // so downstream summarize/smooth can skip re-parsing the CSV. A reservoir sample is a
+    // random draw the cache key cannot capture, so it is never cached.
+    if use_cache && sample_frames.is_none() {
+        let key = crate::cache::cache_key(trajectory_file, cutoff_distance, max_frames, &contacts);
+        let cache_path = crate::cache::cache_path(trajectory_file);
+        crate::cache::write_cache(&cache_path, &key, &results)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to write result cache: {}", e)))?;
+    }
     
     // Convert results to Python list of dicts
     let py_results = PyList::empty_bound(py);
@@ -65,13 +123,19 @@ fn read_trajectory(
         for (cluster, fraction) in &result.clusters_filling {
             py_clusters.set_item(cluster, fraction)?;
         }
-        
+        let py_clusters_soft = PyDict::new_bound(py);
+        for (cluster, fraction) in &result.clusters_filling_soft {
+            py_clusters_soft.set_item(cluster, fraction)?;
+        }
+
         // Build the dictionary
         py_dict.set_item("frame", result.frame)?;
         py_dict.set_item("contacts", result.contacts)?;
         py_dict.set_item("q", result.q)?;
+        py_dict.set_item("q_soft", result.q_soft)?;
         py_dict.set_item("contact_list", py_contact_list)?;
         py_dict.set_item("clusters_filling", py_clusters)?;
+        py_dict.set_item("clusters_filling_soft", py_clusters_soft)?;
         
         py_results.append(py_dict)?;
     }
@@ -81,79 +145,82 @@ fn read_trajectory(
 
 /// Python binding for summarize_trajectory function
 #[pyfunction]
-#[pyo3(signature = (trajectory_file, window_size=10000, cutoff=None, output_csv=None))]
+#[pyo3(signature = (trajectory_file, window_size=10000, cutoff=None, output_csv=None, use_cache=true, output_format="csv", bootstrap=None))]
+#[allow(clippy::too_many_arguments)]
 fn summarize_trajectory(
     py: Python<'_>,
     trajectory_file: &str,
     window_size: usize,
     cutoff: Option<f64>,
     output_csv: Option<&str>,
+    use_cache: bool,
+    output_format: &str,
+    bootstrap: Option<usize>,
 ) -> PyResult<PyObject> {
+    let output_format = parse_output_format(output_format)?;
+
     // Create trajectory reader
     let trajectory = PdbTrajectory::new(trajectory_file);
-    
+
     // Determine output path - auto-generate if None
     let output_path = if let Some(csv_path) = output_csv {
         Some(PathBuf::from(csv_path))
     } else {
         None  // Will be auto-generated in summarize_trajectory
     };
-    
+
     // Summarize trajectory (loads from CSV if results not provided)
     let summaries = trajectory.summarize_trajectory(
         None,  // Load from CSV
         window_size,
         cutoff,
         output_path.as_deref(),
+        use_cache,
+        output_format,
+        bootstrap,
     )
     .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to summarize trajectory: {}", e)))?;
-    
+
     // Convert summaries to Python list of dicts
     let py_summaries = PyList::empty_bound(py);
-    
+
     for summary in summaries {
-        let py_dict = PyDict::new_bound(py);
-        
-        // Convert cluster_means to Python dict
-        let py_clusters = PyDict::new_bound(py);
-        for (cluster, mean) in &summary.cluster_means {
-            py_clusters.set_item(format!("cluster_{}", cluster), mean)?;
-        }
-        
-        // Build the dictionary
-        py_dict.set_item("frame", summary.frame)?;
-        py_dict.set_item("cluster_means", py_clusters)?;
-        
-        py_summaries.append(py_dict)?;
+        py_summaries.append(window_summary_to_py(py, &summary)?)?;
     }
-    
+
     Ok(py_summaries.into())
 }
 
 /// Python binding for smooth function
 #[pyfunction]
-#[pyo3(signature = (trajectory_file, window_size=100, output_csv=None))]
+#[pyo3(signature = (trajectory_file, window_size=100, output_csv=None, use_cache=true, output_format="csv"))]
 fn smooth(
     py: Python<'_>,
     trajectory_file: &str,
     window_size: usize,
     output_csv: Option<&str>,
+    use_cache: bool,
+    output_format: &str,
 ) -> PyResult<PyObject> {
+    let output_format = parse_output_format(output_format)?;
+
     // Create trajectory reader
     let trajectory = PdbTrajectory::new(trajectory_file);
-    
+
     // Determine output path - auto-generate if None
     let output_path = if let Some(csv_path) = output_csv {
         Some(PathBuf::from(csv_path))
     } else {
         None  // Will be auto-generated in smooth
     };
-    
+
     // Smooth trajectory (loads from CSV if results not provided)
     let smoothed = trajectory.smooth(
         None,  // Load from CSV
         window_size,
         output_path.as_deref(),
+        use_cache,
+        output_format,
     )
     .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to smooth trajectory: {}", e)))?;
     
@@ -180,26 +247,46 @@ fn smooth(
     Ok(py_smoothed.into())
 }
 
+/// Build the optional [`Hysteresis`] config from the Python keyword arguments, returning
+/// `None` when debouncing is disabled.
+fn parse_hysteresis(hysteresis: bool, t_up: f64, t_down: f64, min_gap: usize) -> Option<Hysteresis> {
+    if hysteresis {
+        Some(Hysteresis {
+            t_up,
+            t_down,
+            min_gap,
+        })
+    } else {
+        None
+    }
+}
+
 /// Python binding for classify function
 #[pyfunction]
-#[pyo3(signature = (trajectory_file, summary_csv=None, output_path=None))]
+#[pyo3(signature = (trajectory_file, summary_csv=None, output_path=None, hysteresis=false, t_up=1.0, t_down=0.5, min_gap=1))]
+#[allow(clippy::too_many_arguments)]
 fn classify(
     py: Python<'_>,
     trajectory_file: &str,
     summary_csv: Option<&str>,
     output_path: Option<&str>,
+    hysteresis: bool,
+    t_up: f64,
+    t_down: f64,
+    min_gap: usize,
 ) -> PyResult<PyObject> {
     // Create trajectory reader
     let trajectory = PdbTrajectory::new(trajectory_file);
-    
+
     // Determine paths
     let summary_path = summary_csv.map(|p| PathBuf::from(p));
     let output_path_buf = output_path.map(|p| PathBuf::from(p));
-    
+
     // Classify trajectory
     let formation_order = trajectory.classify(
         summary_path.as_deref(),
         output_path_buf.as_deref(),
+        parse_hysteresis(hysteresis, t_up, t_down, min_gap),
     )
     .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to classify trajectory: {}", e)))?;
     
@@ -212,6 +299,283 @@ fn classify(
     Ok(py_order.into())
 }
 
+/// Convert a slice of `FrameResult` into a Python list of dicts.
+fn frame_results_to_py(py: Python<'_>, results: &[FrameResult]) -> PyResult<PyObject> {
+    let py_results = PyList::empty_bound(py);
+    for result in results {
+        let py_dict = PyDict::new_bound(py);
+
+        let py_contact_list = PyList::empty_bound(py);
+        for (i, j) in &result.contact_list {
+            let py_tuple = PyTuple::new_bound(py, &[i.into_py(py), j.into_py(py)]);
+            py_contact_list.append(py_tuple)?;
+        }
+
+        let py_clusters = PyDict::new_bound(py);
+        for (cluster, fraction) in &result.clusters_filling {
+            py_clusters.set_item(cluster, fraction)?;
+        }
+        let py_clusters_soft = PyDict::new_bound(py);
+        for (cluster, fraction) in &result.clusters_filling_soft {
+            py_clusters_soft.set_item(cluster, fraction)?;
+        }
+
+        py_dict.set_item("frame", result.frame)?;
+        py_dict.set_item("contacts", result.contacts)?;
+        py_dict.set_item("q", result.q)?;
+        py_dict.set_item("q_soft", result.q_soft)?;
+        py_dict.set_item("contact_list", py_contact_list)?;
+        py_dict.set_item("clusters_filling", py_clusters)?;
+        py_dict.set_item("clusters_filling_soft", py_clusters_soft)?;
+
+        py_results.append(py_dict)?;
+    }
+    Ok(py_results.into())
+}
+
+/// Convert one `WindowSummary` into a Python dict, including the bootstrap CI bounds as
+/// `cluster_ci` (a `{cluster: (low, high)}` dict) when they were computed.
+fn window_summary_to_py(py: Python<'_>, summary: &WindowSummary) -> PyResult<PyObject> {
+    let py_dict = PyDict::new_bound(py);
+
+    let py_clusters = PyDict::new_bound(py);
+    for (cluster, mean) in &summary.cluster_means {
+        py_clusters.set_item(format!("cluster_{}", cluster), mean)?;
+    }
+    py_dict.set_item("frame", summary.frame)?;
+    py_dict.set_item("cluster_means", py_clusters)?;
+
+    if let Some(ci) = &summary.cluster_ci {
+        let py_ci = PyDict::new_bound(py);
+        for (cluster, (low, high)) in ci {
+            let bounds = PyTuple::new_bound(py, &[low.into_py(py), high.into_py(py)]);
+            py_ci.set_item(format!("cluster_{}", cluster), bounds)?;
+        }
+        py_dict.set_item("cluster_ci", py_ci)?;
+    }
+
+    Ok(py_dict.into())
+}
+
+/// Stateful trajectory handle that parses the PDB and contact map once and keeps the
+/// per-frame results in memory, so chained analyses don't round-trip through disk.
+///
+/// Construct once with the trajectory and contact paths, then call `.analyze()`,
+/// `.summarize()`, `.smooth()` and `.classify()` repeatedly against the cached parse.
+#[pyclass(name = "Trajectory")]
+struct PyTrajectory {
+    trajectory: PdbTrajectory,
+    contacts: Vec<Contact>,
+    /// Parsed per-frame results; populated on the first `analyze()` call.
+    results: Option<Vec<FrameResult>>,
+}
+
+#[pymethods]
+impl PyTrajectory {
+    #[new]
+    #[pyo3(signature = (trajectory_file, contacts_file))]
+    fn new(trajectory_file: &str, contacts_file: &str) -> PyResult<Self> {
+        let contacts = load_contacts(contacts_file).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Failed to load contacts: {}", e))
+        })?;
+        Ok(Self {
+            trajectory: PdbTrajectory::new(trajectory_file),
+            contacts,
+            results: None,
+        })
+    }
+
+    /// Parse the trajectory and compute native-contact formation for every frame,
+    /// caching the result for subsequent calls. Returns the per-frame results.
+    #[pyo3(signature = (cutoff=1.2, beta=5.0, lambda=1.2, max_frames=None, sample_frames=None, seed=None, num_workers=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn analyze(
+        &mut self,
+        py: Python<'_>,
+        cutoff: f64,
+        beta: f64,
+        lambda: f64,
+        max_frames: Option<usize>,
+        sample_frames: Option<usize>,
+        seed: Option<u64>,
+        num_workers: Option<usize>,
+    ) -> PyResult<PyObject> {
+        let results = py
+            .allow_threads(|| {
+                self.trajectory.read_trajectory_pooled(
+                    &self.contacts,
+                    cutoff,
+                    beta,
+                    lambda,
+                    max_frames,
+                    sample_frames,
+                    seed,
+                    num_workers.unwrap_or(0),
+                    None,
+                    OutputFormat::Csv,
+                    1000,
+                    |_, _| {},
+                )
+            })
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to analyze trajectory: {}",
+                    e
+                ))
+            })?;
+        let py_results = frame_results_to_py(py, &results)?;
+        self.results = Some(results);
+        Ok(py_results)
+    }
+
+    /// Summarize the cached per-frame results into per-window cluster means, optionally
+    /// attaching bootstrap 95% confidence intervals when `bootstrap` resamples are given.
+    #[pyo3(signature = (window=10000, cutoff=None, bootstrap=None))]
+    fn summarize(
+        &self,
+        py: Python<'_>,
+        window: usize,
+        cutoff: Option<f64>,
+        bootstrap: Option<usize>,
+    ) -> PyResult<PyObject> {
+        let results = self.require_results()?;
+        let summaries = self
+            .trajectory
+            .summarize_trajectory(Some(results), window, cutoff, None, false, OutputFormat::Csv, bootstrap)
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to summarize trajectory: {}",
+                    e
+                ))
+            })?;
+
+        let py_summaries = PyList::empty_bound(py);
+        for summary in summaries {
+            py_summaries.append(window_summary_to_py(py, &summary)?)?;
+        }
+        Ok(py_summaries.into())
+    }
+
+    /// Smooth the cached per-frame results with a centered running average.
+    #[pyo3(signature = (window=100))]
+    fn smooth(&self, py: Python<'_>, window: usize) -> PyResult<PyObject> {
+        let results = self.require_results()?;
+        let smoothed = self
+            .trajectory
+            .smooth(Some(results), window, None, false, OutputFormat::Csv)
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to smooth trajectory: {}",
+                    e
+                ))
+            })?;
+
+        let py_smoothed = PyList::empty_bound(py);
+        for result in smoothed {
+            let py_dict = PyDict::new_bound(py);
+            let py_clusters = PyDict::new_bound(py);
+            for (cluster, smooth_val) in &result.cluster_smooth {
+                py_clusters.set_item(format!("cluster_{}_smooth", cluster), smooth_val)?;
+            }
+            py_dict.set_item("frame", result.frame)?;
+            py_dict.set_item("q_smooth", result.q_smooth)?;
+            py_dict.set_item("cluster_smooth", py_clusters)?;
+            py_smoothed.append(py_dict)?;
+        }
+        Ok(py_smoothed.into())
+    }
+
+    /// Determine the cluster formation order from the cached results, summarizing into
+    /// binary windows in memory rather than reloading a summary CSV from disk.
+    #[pyo3(signature = (window=10000, cutoff=0.5, hysteresis=false, t_up=1.0, t_down=0.5, min_gap=1))]
+    #[allow(clippy::too_many_arguments)]
+    fn classify(
+        &self,
+        py: Python<'_>,
+        window: usize,
+        cutoff: f64,
+        hysteresis: bool,
+        t_up: f64,
+        t_down: f64,
+        min_gap: usize,
+    ) -> PyResult<PyObject> {
+        let results = self.require_results()?;
+        let summaries = self
+            .trajectory
+            .summarize_trajectory(Some(results), window, Some(cutoff), None, false, OutputFormat::Csv, None)
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to summarize trajectory: {}",
+                    e
+                ))
+            })?;
+
+        let hysteresis = parse_hysteresis(hysteresis, t_up, t_down, min_gap);
+        let formation_order = determine_formation_order(summaries, hysteresis).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to classify trajectory: {}",
+                e
+            ))
+        })?;
+
+        let py_order = PyList::empty_bound(py);
+        for cluster_num in formation_order {
+            py_order.append(cluster_num)?;
+        }
+        Ok(py_order.into())
+    }
+
+    /// Embed the cached per-frame contact vectors into 2D with t-SNE, producing a map of
+    /// the folding landscape. Returns a list of `{frame, x, y}` dicts, one per retained
+    /// frame; `stride` subsamples long trajectories (the pairwise step is O(N²)).
+    #[pyo3(signature = (stride=1, perplexity=30.0, iterations=1000, learning_rate=200.0))]
+    fn tsne(
+        &self,
+        py: Python<'_>,
+        stride: usize,
+        perplexity: f64,
+        iterations: usize,
+        learning_rate: f64,
+    ) -> PyResult<PyObject> {
+        let results = self.require_results()?;
+        let params = TsneParams {
+            stride,
+            perplexity,
+            iterations,
+            learning_rate,
+        };
+        let points = py
+            .allow_threads(|| tsne_embedding(results, &params))
+            .map_err(|e| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Failed to embed trajectory: {}",
+                    e
+                ))
+            })?;
+
+        let py_points = PyList::empty_bound(py);
+        for point in points {
+            let py_dict = PyDict::new_bound(py);
+            py_dict.set_item("frame", point.frame)?;
+            py_dict.set_item("x", point.x)?;
+            py_dict.set_item("y", point.y)?;
+            py_points.append(py_dict)?;
+        }
+        Ok(py_points.into())
+    }
+}
+
+impl PyTrajectory {
+    /// Return the cached results or a helpful error if `analyze()` hasn't been called.
+    fn require_results(&self) -> PyResult<&[FrameResult]> {
+        self.results.as_deref().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Trajectory not analyzed yet. Call analyze() before summarize/smooth/classify.",
+            )
+        })
+    }
+}
+
 /// Python module definition
 #[pymodule]
 fn folding_analysis_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -219,6 +583,7 @@ fn folding_analysis_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(summarize_trajectory, m)?)?;
     m.add_function(wrap_pyfunction!(smooth, m)?)?;
     m.add_function(wrap_pyfunction!(classify, m)?)?;
+    m.add_class::<PyTrajectory>()?;
     m.add("__doc__", "Folding analysis Rust library with Python bindings")?;
     Ok(())
 }
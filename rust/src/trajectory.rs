@@ -1,23 +1,32 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
 use crate::contacts::Contact;
-use crate::structure::{Coordinate, FrameData};
+use crate::parquet_io::OutputFormat;
+use crate::structure::FrameData;
 use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
 
 /// Trajectory data: maps frame number to frame data
 pub type TrajectoryData = HashMap<i32, FrameData>;
 
 /// Result of trajectory analysis for a single frame
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FrameResult {
     pub frame: i32,
     pub contacts: usize,
     pub q: f64,
+    /// Continuous Best–Hummer Q: mean over native pairs of the smooth formation factor
+    /// `1 / (1 + exp(beta*(d - lambda*r)))`, a differentiable alternative to the hard
+    /// cutoff `q`.
+    pub q_soft: f64,
     pub contact_list: Vec<(i32, i32)>,
     pub clusters_filling: HashMap<i32, f64>,
+    /// Per-cluster soft filling, computed the same way as `q_soft` but per cluster.
+    pub clusters_filling_soft: HashMap<i32, f64>,
 }
 
 /// Summary of trajectory analysis for a window of frames
@@ -25,6 +34,9 @@ pub struct FrameResult {
 pub struct WindowSummary {
     pub frame: i32,  // Starting frame of the window
     pub cluster_means: HashMap<i32, f64>,  // Mean fraction for each cluster
+    /// Bootstrap 95% confidence interval `(lower, upper)` for each cluster mean, present
+    /// only when `summarize_trajectory` was called with `bootstrap` resampling enabled.
+    pub cluster_ci: Option<HashMap<i32, (f64, f64)>>,
 }
 
 /// Smoothed result for a single frame (running average)
@@ -53,17 +65,46 @@ pub trait Trajectory {
     /// # Arguments
     /// * `contacts` - Vector of Contact structs with native contact information
     /// * `cutoff_distance` - Multiplier for native distance cutoff (default: 1.2)
+    /// * `beta` - Steepness (Å⁻¹) of the smooth Best–Hummer Q (default: ~5.0)
+    /// * `lambda` - Tolerance multiplier on the native distance for the smooth Q (default: ~1.2)
     /// * `max_frames` - Maximum number of frames to process (None for all frames)
+    /// * `sample_frames` - When set, reservoir-sample this many frames instead of truncating
+    ///   to the first `max_frames` models
+    /// * `sample_seed` - Optional RNG seed making the reservoir draw reproducible
+    /// * `start_frame` - Skip frames with a true trajectory index below this one (None for
+    ///   no lower bound). Applied during parsing, before `max_frames`; ignored when
+    ///   `sample_frames` is set.
+    /// * `end_frame` - Stop once a frame's true trajectory index exceeds this one (None for
+    ///   no upper bound)
+    /// * `stride` - Keep only every Nth frame surviving the `start_frame`/`end_frame`
+    ///   window (None or `Some(1)` keeps them all). The emitted `frame` stays the true
+    ///   trajectory index, not a post-stride counter, so window-based downstream steps
+    ///   stay aligned with simulation time.
     /// * `output_csv_path` - Optional path to save CSV output. If None, auto-generates from input path
-    /// 
+    /// * `use_cache` - When true, write a bincode `.traj.cache` sidecar of the results (and reuse a
+    ///   valid one if present) so downstream steps can skip re-parsing the CSV
+    /// * `output_format` - Backend for the saved file: [`OutputFormat::Csv`] for plain text,
+    ///   [`OutputFormat::Parquet`] for a compressed, typed, columnar file, or
+    ///   [`OutputFormat::Bin`] for the compact [`crate::bin_io`] layout
+    ///
     /// # Returns
     /// Vector of FrameResult for each frame
+    #[allow(clippy::too_many_arguments)]
     fn read_trajectory(
         &self,
         contacts: &[Contact],
         cutoff_distance: f64,
+        beta: f64,
+        lambda: f64,
         max_frames: Option<usize>,
+        sample_frames: Option<usize>,
+        sample_seed: Option<u64>,
+        start_frame: Option<i32>,
+        end_frame: Option<i32>,
+        stride: Option<usize>,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
     ) -> Result<Vec<FrameResult>, String>;
     
     /// Summarize trajectory by calculating mean cluster filling fractions in windows.
@@ -78,7 +119,14 @@ pub trait Trajectory {
     /// * `cutoff` - Optional cutoff for binary conversion. If Some(value), convert probabilities
     ///   to binary (0 or 1). Values >= cutoff become 1, values < cutoff become 0.
     /// * `output_csv_path` - Optional path to save summary CSV. If None, auto-generates from input path
-    /// 
+    /// * `use_cache` - When true and `results` is None, load the `.traj.cache` cache sidecar
+    ///   (if fresh) instead of re-parsing the parsed CSV
+    /// * `output_format` - Backend for the saved summary file ([`OutputFormat::Csv`] or
+    ///   [`OutputFormat::Parquet`])
+    /// * `bootstrap` - When Some(n), bootstrap-resample each window's per-cluster frames n
+    ///   times to attach a 95% confidence interval to its mean (None skips this and leaves
+    ///   `cluster_ci` empty)
+    ///
     /// # Returns
     /// Vector of WindowSummary for each window
     fn summarize_trajectory(
@@ -87,6 +135,9 @@ pub trait Trajectory {
         window_size: usize,
         cutoff: Option<f64>,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
+        bootstrap: Option<usize>,
     ) -> Result<Vec<WindowSummary>, String>;
     
     /// Smooth trajectory data by calculating running averages.
@@ -99,7 +150,11 @@ pub trait Trajectory {
     ///   from auto-generated CSV path based on trajectory file name.
     /// * `window_size` - Window size for running average (default: 100)
     /// * `output_csv_path` - Optional path to save smoothed CSV. If None, auto-generates from input path
-    /// 
+    /// * `use_cache` - When true and `results` is None, load the `.traj.cache` cache sidecar
+    ///   (if fresh) instead of re-parsing the parsed CSV
+    /// * `output_format` - Backend for the saved smoothed file ([`OutputFormat::Csv`] or
+    ///   [`OutputFormat::Parquet`])
+    ///
     /// # Returns
     /// Vector of SmoothedResult for each frame
     fn smooth(
@@ -107,6 +162,8 @@ pub trait Trajectory {
         results: Option<&[FrameResult]>,
         window_size: usize,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
     ) -> Result<Vec<SmoothedResult>, String>;
     
     /// Classify trajectory by determining cluster formation order.
@@ -124,6 +181,7 @@ pub trait Trajectory {
         &self,
         summary_csv_path: Option<&Path>,
         output_path: Option<&Path>,
+        hysteresis: Option<Hysteresis>,
     ) -> Result<Vec<i32>, String>;
 }
 
@@ -138,141 +196,214 @@ impl PdbTrajectory {
             file_path: file_path.as_ref().to_string_lossy().to_string(),
         }
     }
-}
 
-impl Trajectory for PdbTrajectory {
-    fn read_pdb(&self, max_frames: Option<usize>) -> Result<TrajectoryData, String> {
-        let file = File::open(&self.file_path)
-            .map_err(|e| format!("Failed to open PDB file {}: {}", self.file_path, e))?;
-        
-        let reader = BufReader::new(file);
-        let mut frames_data = TrajectoryData::new();
-        
-        let mut current_model: Option<i32> = None;
-        let mut current_residue_coords = FrameData::new();
-        let mut model_found = false;
-        let mut model_saved = false;
-        
-        for line_result in reader.lines() {
-            let line = line_result.map_err(|e| format!("Error reading line: {}", e))?;
-            
-            if line.starts_with("MODEL") {
-                // Start of new model
-                model_found = true;
-                
-                // Save previous model if it hasn't been saved yet
-                if let Some(model_num) = current_model.take() {
-                    if !model_saved {
-                        frames_data.insert(model_num, current_residue_coords.clone());
-                    }
-                }
-                
-                // Check if we've reached max_frames limit
-                if let Some(max) = max_frames {
-                    if frames_data.len() >= max {
-                        break;
-                    }
-                }
-                
-                // Parse model number
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() < 2 {
-                    return Err(format!("Invalid MODEL line: {}", line));
-                }
-                
-                current_model = Some(
-                    parts[1]
-                        .parse::<i32>()
-                        .map_err(|e| format!("Failed to parse model number: {}", e))?,
-                );
-                current_residue_coords = FrameData::new();
-                model_saved = false;
-                
-            } else if line.starts_with("ATOM") && line.contains(" CA ") {
-                // Extract CA atom coordinates
-                // PDB format: columns 22-26 = residue number, 30-38 = x, 38-46 = y, 46-54 = z
-                if line.len() < 54 {
-                    continue; // Skip malformed lines
-                }
-                
-                // Extract residue number (columns 22-26, 1-indexed)
-                let residue_str = line.get(22..26).unwrap_or("").trim();
-                let residue_num = match residue_str.parse::<i32>() {
-                    Ok(n) => n,
-                    Err(_) => continue, // Skip if can't parse residue number
-                };
-                
-                // Extract coordinates
-                let x_str = line.get(30..38).unwrap_or("").trim();
-                let y_str = line.get(38..46).unwrap_or("").trim();
-                let z_str = line.get(46..54).unwrap_or("").trim();
-                
-                let x = match x_str.parse::<f64>() {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-                let y = match y_str.parse::<f64>() {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-                let z = match z_str.parse::<f64>() {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-                
-                current_residue_coords.insert(residue_num, Coordinate::new(x, y, z));
-                
-            } else if line.starts_with("ENDMDL") {
-                // End of model - save the data
-                if let Some(model_num) = current_model {
-                    if !model_saved {
-                        frames_data.insert(model_num, current_residue_coords.clone());
-                        model_saved = true;
-                        current_residue_coords = FrameData::new();
-                    }
-                }
-                
-                // Check if we've reached max_frames limit
-                if let Some(max) = max_frames {
-                    if frames_data.len() >= max {
-                        break;
+    /// Read trajectory and calculate native contact formation using a worker pool.
+    ///
+    /// Frame-level contact/Q computation is independent across frames, so the work is
+    /// fanned out to `num_workers` threads over a channel (0 = use all available cores).
+    /// Results are collected and re-sorted by frame index before returning (and before
+    /// writing the CSV) so the output ordering is deterministic regardless of the order
+    /// in which workers complete.
+    ///
+    /// `progress` is invoked roughly every `progress_every` frames with
+    /// `(frames_done, Some(total_frames))` so callers can surface progress; pass a
+    /// no-op closure to disable it.
+    ///
+    /// When `sample_frames` is set the frames are reservoir-sampled (see
+    /// [`read_pdb_sampled`](Self::read_pdb_sampled)) instead of truncated by `max_frames`,
+    /// with `sample_seed` controlling reproducibility.
+    pub fn read_trajectory_pooled<F>(
+        &self,
+        contacts: &[Contact],
+        cutoff_distance: f64,
+        beta: f64,
+        lambda: f64,
+        max_frames: Option<usize>,
+        sample_frames: Option<usize>,
+        sample_seed: Option<u64>,
+        num_workers: usize,
+        output_csv_path: Option<&Path>,
+        output_format: OutputFormat,
+        progress_every: usize,
+        mut progress: F,
+    ) -> Result<Vec<FrameResult>, String>
+    where
+        F: FnMut(usize, Option<usize>),
+    {
+        let frames_data = self.load_frames(max_frames, sample_frames, sample_seed, None, None, None)?;
+
+        if frames_data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Pre-compute cluster sizes once; shared read-only across workers.
+        let mut cluster_contacts: HashMap<i32, Vec<usize>> = HashMap::new();
+        for (idx, contact) in contacts.iter().enumerate() {
+            cluster_contacts
+                .entry(contact.cluster)
+                .or_insert_with(Vec::new)
+                .push(idx);
+        }
+        let cluster_sizes: HashMap<i32, usize> = cluster_contacts
+            .iter()
+            .map(|(cluster, indices)| (*cluster, indices.len()))
+            .collect();
+        let total_contacts = contacts.len();
+
+        let mut sorted_frames: Vec<i32> = frames_data.keys().copied().collect();
+        sorted_frames.sort();
+        let total_frames = sorted_frames.len();
+
+        // Resolve worker count (0 = all cores).
+        let workers = if num_workers == 0 {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        } else {
+            num_workers
+        }
+        .max(1)
+        .min(total_frames.max(1));
+
+        // Feed frame indices to workers over a channel; each worker pulls work and
+        // pushes back a FrameResult on the result channel.
+        let (job_tx, job_rx) = mpsc::channel::<i32>();
+        let job_rx = std::sync::Arc::new(std::sync::Mutex::new(job_rx));
+        let (res_tx, res_rx) = mpsc::channel::<FrameResult>();
+
+        let results = thread::scope(|scope| -> Result<Vec<FrameResult>, String> {
+            for _ in 0..workers {
+                let job_rx = std::sync::Arc::clone(&job_rx);
+                let res_tx = res_tx.clone();
+                let frames_data = &frames_data;
+                let cluster_sizes = &cluster_sizes;
+                scope.spawn(move || {
+                    loop {
+                        let frame_num = {
+                            let guard = job_rx.lock().unwrap();
+                            guard.recv()
+                        };
+                        let frame_num = match frame_num {
+                            Ok(f) => f,
+                            Err(_) => break,
+                        };
+                        let coords = &frames_data[&frame_num];
+                        let result = evaluate_frame(
+                            frame_num,
+                            coords,
+                            contacts,
+                            cluster_sizes,
+                            total_contacts,
+                            cutoff_distance,
+                            beta,
+                            lambda,
+                        );
+                        // A send error just means the collector is gone; stop.
+                        if res_tx.send(result).is_err() {
+                            break;
+                        }
                     }
-                }
+                });
             }
-        }
-        
-        // Handle last model if file doesn't end with ENDMDL
-        if max_frames.is_none() || frames_data.len() < max_frames.unwrap_or(0) {
-            if let Some(model_num) = current_model {
-                if !model_saved && !current_residue_coords.is_empty() {
-                    frames_data.insert(model_num, current_residue_coords.clone());
-                }
+            // Drop our own sender so the result channel closes once workers finish.
+            drop(res_tx);
+
+            for &frame_num in &sorted_frames {
+                job_tx
+                    .send(frame_num)
+                    .map_err(|e| format!("Failed to dispatch frame to worker pool: {}", e))?;
             }
-            
-            // Handle single-model files without MODEL/ENDMDL markers
-            if !model_found && !current_residue_coords.is_empty() {
-                frames_data.insert(1, current_residue_coords);
+            drop(job_tx);
+
+            // Collect results as they arrive, reporting progress periodically.
+            let mut results = Vec::with_capacity(total_frames);
+            let progress_every = progress_every.max(1);
+            while let Ok(result) = res_rx.recv() {
+                results.push(result);
+                if results.len() % progress_every == 0 || results.len() == total_frames {
+                    progress(results.len(), Some(total_frames));
+                }
             }
+
+            // Re-sort by frame index for deterministic output ordering.
+            results.sort_by_key(|r| r.frame);
+            Ok(results)
+        })?;
+
+        if let Some(output_path) = output_csv_path {
+            save_results(&results, output_path, output_format)?;
         }
-        
-        Ok(frames_data)
+
+        Ok(results)
     }
-    
-    fn read_trajectory(
+
+    /// Read the trajectory and compute per-frame contacts with a rayon parallel iterator.
+    ///
+    /// The per-frame work is embarrassingly parallel, so [`evaluate_frame`] is driven over
+    /// the frames with `par_iter`; collecting from an indexed `Vec` preserves frame order
+    /// without a re-sort. The `indicatif` bar is advanced from an [`AtomicUsize`] bumped
+    /// inside the parallel closure. `num_threads` caps parallelism via a scoped rayon pool
+    /// (`None` = use all cores). When `use_cache` is set, a bincode cache sidecar whose key
+    /// matches is reused and a fresh parse rewrites it, except when `sample_frames` selects reservoir sampling or a
+    /// `start_frame`/`end_frame`/`stride` window is active — neither is captured by the
+    /// cache key, so the cache is bypassed in both cases.
+    #[allow(clippy::too_many_arguments)]
+    pub fn read_trajectory_parallel(
         &self,
         contacts: &[Contact],
         cutoff_distance: f64,
+        beta: f64,
+        lambda: f64,
         max_frames: Option<usize>,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        num_threads: Option<usize>,
+        output_format: OutputFormat,
+        sample_frames: Option<usize>,
+        sample_seed: Option<u64>,
+        start_frame: Option<i32>,
+        end_frame: Option<i32>,
+        stride: Option<usize>,
     ) -> Result<Vec<FrameResult>, String> {
-        // Read PDB file
-        let frames_data = self.read_pdb(max_frames)?;
-        
+        // A reservoir sample is a fresh random draw, and a frame-range/stride window is a
+        // subset selection, neither captured by the cache key, so the cache is bypassed
+        // whenever either is active.
+        let ranged = start_frame.is_some() || end_frame.is_some() || stride.is_some();
+        let use_cache = use_cache && sample_frames.is_none() && !ranged;
+        // If a valid cache sidecar exists, reuse it and skip the expensive parse.
+        let cache_key = if use_cache {
+            Some(crate::cache::cache_key(
+                &self.file_path,
+                cutoff_distance,
+                max_frames,
+                contacts,
+            ))
+        } else {
+            None
+        };
+        let cache_path = crate::cache::cache_path(&self.file_path);
+        if let Some(key) = cache_key.as_deref() {
+            if let Some(cached) = crate::cache::load_cache(&cache_path, key) {
+                if let Some(output_path) = output_csv_path {
+                    save_results(&cached, output_path, output_format)?;
+                }
+                return Ok(cached);
+            }
+        }
+
+        let frames_data = self.load_frames(
+            max_frames,
+            sample_frames,
+            sample_seed,
+            start_frame,
+            end_frame,
+            stride,
+        )?;
         if frames_data.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Pre-compute cluster information
+
+        // Pre-compute cluster sizes once; shared read-only across the parallel work.
         let mut cluster_contacts: HashMap<i32, Vec<usize>> = HashMap::new();
         for (idx, contact) in contacts.iter().enumerate() {
             cluster_contacts
@@ -280,20 +411,16 @@ impl Trajectory for PdbTrajectory {
                 .or_insert_with(Vec::new)
                 .push(idx);
         }
-        
         let cluster_sizes: HashMap<i32, usize> = cluster_contacts
             .iter()
             .map(|(cluster, indices)| (*cluster, indices.len()))
             .collect();
-        
         let total_contacts = contacts.len();
-        
-        // Process each frame with progress bar
-        let mut results = Vec::new();
+
         let mut sorted_frames: Vec<i32> = frames_data.keys().copied().collect();
         sorted_frames.sort();
-        
         let total_frames = sorted_frames.len();
+
         let pb = ProgressBar::new(total_frames as u64);
         pb.set_style(
             ProgressStyle::default_bar()
@@ -302,105 +429,280 @@ impl Trajectory for PdbTrajectory {
                 .progress_chars("#>-")
         );
         pb.set_message("Processing trajectory frames");
-        
+
+        let counter = AtomicUsize::new(0);
+        let compute = || {
+            sorted_frames
+                .par_iter()
+                .map(|&frame_num| {
+                    let residue_coords = &frames_data[&frame_num];
+                    let result = evaluate_frame(
+                        frame_num,
+                        residue_coords,
+                        contacts,
+                        &cluster_sizes,
+                        total_contacts,
+                        cutoff_distance,
+                        beta,
+                        lambda,
+                    );
+                    // Advancing by the running count keeps the bar monotonic even though
+                    // frames finish out of order.
+                    let done = counter.fetch_add(1, Ordering::Relaxed) + 1;
+                    pb.set_position(done as u64);
+                    result
+                })
+                .collect::<Vec<FrameResult>>()
+        };
+
+        // Collecting from the ordered `sorted_frames` keeps results in frame order.
+        let results = match num_threads {
+            Some(n) if n > 0 => rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(|e| format!("Failed to build thread pool: {}", e))?
+                .install(compute),
+            _ => compute(),
+        };
+
+        pb.finish_with_message("Processing complete");
+
+        if let Some(output_path) = output_csv_path {
+            save_results(&results, output_path, output_format)?;
+        }
+        if let Some(key) = cache_key.as_deref() {
+            crate::cache::write_cache(&cache_path, key, &results)?;
+        }
+
+        Ok(results)
+    }
+
+    /// Discover *all* residue pairs in contact per frame, not just the predefined native
+    /// set, so non-native contact formation during folding can be studied.
+    ///
+    /// Rather than an O(N²) all-pairs scan, each frame is bucketed into a uniform grid
+    /// whose cell side equals `cutoff_angstrom`; a residue can then only be in contact with
+    /// residues in its own cell or the 26 neighbouring cells, which bounds the work to the
+    /// local neighbourhood. Pairs are recorded with sequence separation `|i - j| >= 3` to
+    /// skip trivial backbone neighbours.
+    ///
+    /// The returned [`FrameResult`]s carry the discovered pairs in `contact_list` (and
+    /// their count in `contacts`); de-novo mode has no native reference set, so `q`,
+    /// `q_soft` and the per-cluster maps are left at their empty defaults.
+    pub fn detect_contacts(
+        &self,
+        cutoff_angstrom: f64,
+        max_frames: Option<usize>,
+    ) -> Result<Vec<FrameResult>, String> {
+        if cutoff_angstrom <= 0.0 {
+            return Err(format!(
+                "Contact cutoff must be positive, got {}",
+                cutoff_angstrom
+            ));
+        }
+
+        let frames_data = self.read_pdb(max_frames)?;
+        let mut sorted_frames: Vec<i32> = frames_data.keys().copied().collect();
+        sorted_frames.sort();
+
+        let mut results = Vec::with_capacity(sorted_frames.len());
         for frame_num in sorted_frames {
-            let residue_coords = &frames_data[&frame_num];
-            
-            let mut existing_contacts = Vec::new();
-            let mut cluster_counts: HashMap<i32, usize> = HashMap::new();
-            
-            // Check each native contact
-            for contact in contacts {
-                // Check if both residues exist in the structure
-                if let (Some(coord_i), Some(coord_j)) = 
-                    (residue_coords.get(&contact.i), residue_coords.get(&contact.j)) {
-                    // Calculate distance between CA atoms
-                    let distance = coord_i.distance_to(coord_j);
-                    
-                    // Check if contact exists (distance < r_native * cutoff_distance)
-                    if distance < contact.r * cutoff_distance {
-                        existing_contacts.push((contact.i, contact.j));
-                        *cluster_counts.entry(contact.cluster).or_insert(0) += 1;
-                    }
+            let coords = &frames_data[&frame_num];
+            let contact_list = detect_frame_contacts(coords, cutoff_angstrom);
+            results.push(FrameResult {
+                frame: frame_num,
+                contacts: contact_list.len(),
+                q: 0.0,
+                q_soft: 0.0,
+                contact_list,
+                clusters_filling: HashMap::new(),
+                clusters_filling_soft: HashMap::new(),
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Stream the trajectory and keep a uniform random subset of `sample_frames` frames
+    /// using Algorithm R reservoir sampling.
+    ///
+    /// Unlike `max_frames`, which biases analysis toward the start of the trajectory by
+    /// truncating to the first N models, this draws a statistically representative subset
+    /// in a single streaming pass with memory bounded to `sample_frames` frames: the first
+    /// N frames fill the reservoir, and each subsequent i-th frame (0-indexed) replaces a
+    /// uniformly chosen slot with probability `N / (i + 1)`. Selected frames keep their
+    /// original model numbers as the `TrajectoryData` key; `seed` makes the draw
+    /// reproducible (a time-based seed is used when `None`).
+    pub fn read_pdb_sampled(
+        &self,
+        sample_frames: usize,
+        seed: Option<u64>,
+    ) -> Result<TrajectoryData, String> {
+        if sample_frames == 0 {
+            return Ok(TrajectoryData::new());
+        }
+
+        let format = crate::formats::open_trajectory(&self.file_path)?;
+        let mut rng = SplitMix64::new(seed.unwrap_or_else(default_sample_seed));
+        let mut reservoir: Vec<(i32, FrameData)> = Vec::with_capacity(sample_frames);
+
+        for (i, frame) in format.frames()?.enumerate() {
+            let (frame_num, coords) = frame?;
+            if i < sample_frames {
+                reservoir.push((frame_num, coords));
+            } else {
+                // Replace a uniformly chosen slot with probability N / (i + 1).
+                let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                if j < sample_frames {
+                    reservoir[j] = (frame_num, coords);
                 }
             }
-            
-            // Calculate q (fraction of native contacts existing)
-            let q = if total_contacts > 0 {
-                existing_contacts.len() as f64 / total_contacts as f64
-            } else {
-                0.0
-            };
-            
-            // Calculate clusters_filling (fraction of contacts in each cluster)
-            let mut clusters_filling = HashMap::new();
-            for (cluster_num, count) in cluster_counts.iter() {
-                let cluster_size = cluster_sizes.get(cluster_num).copied().unwrap_or(0);
-                if cluster_size > 0 {
-                    clusters_filling.insert(*cluster_num, *count as f64 / cluster_size as f64);
-                } else {
-                    clusters_filling.insert(*cluster_num, 0.0);
+        }
+
+        Ok(reservoir.into_iter().collect())
+    }
+
+    /// Load the frames to analyze, reservoir-sampling `sample_frames` of them when set and
+    /// otherwise taking the `start_frame`/`end_frame`/`stride`-selected window (or all
+    /// frames), truncated to the first `max_frames` surviving models.
+    ///
+    /// `start_frame`/`end_frame`/`stride` are ignored when `sample_frames` is set — a
+    /// reservoir draw already picks its own frames from the whole trajectory.
+    #[allow(clippy::too_many_arguments)]
+    fn load_frames(
+        &self,
+        max_frames: Option<usize>,
+        sample_frames: Option<usize>,
+        seed: Option<u64>,
+        start_frame: Option<i32>,
+        end_frame: Option<i32>,
+        stride: Option<usize>,
+    ) -> Result<TrajectoryData, String> {
+        match sample_frames {
+            Some(n) => self.read_pdb_sampled(n, seed),
+            None => self.read_pdb_range(max_frames, start_frame, end_frame, stride),
+        }
+    }
+
+    /// Read frames restricted to the true-trajectory-index window
+    /// `[start_frame, end_frame]`, keeping only every `stride`-th surviving frame before
+    /// `max_frames` truncates the result — so the expensive per-frame contact computation
+    /// downstream never runs on a frame the caller asked to skip. `frame_num` keys stay the
+    /// true trajectory index throughout, not a post-stride counter.
+    pub fn read_pdb_range(
+        &self,
+        max_frames: Option<usize>,
+        start_frame: Option<i32>,
+        end_frame: Option<i32>,
+        stride: Option<usize>,
+    ) -> Result<TrajectoryData, String> {
+        // Dispatch to the right format reader by extension (PDB text, DCD, XTC, ...) so the
+        // same contact/Q pipeline runs on binary MD trajectories without pre-converting.
+        let format = crate::formats::open_trajectory(&self.file_path)?;
+        let stride = stride.unwrap_or(1).max(1);
+
+        let mut frames_data = TrajectoryData::new();
+        let mut kept = 0usize;
+        for frame in format.frames()? {
+            let (frame_num, coords) = frame?;
+            if let Some(s) = start_frame {
+                if frame_num < s {
+                    continue;
                 }
             }
-            
-            // Ensure all clusters are represented (even if no contacts formed)
-            for cluster_num in cluster_sizes.keys() {
-                clusters_filling.entry(*cluster_num).or_insert(0.0);
+            if let Some(e) = end_frame {
+                if frame_num > e {
+                    break;
+                }
             }
-            
-            results.push(FrameResult {
-                frame: frame_num,
-                contacts: existing_contacts.len(),
-                q,
-                contact_list: existing_contacts,
-                clusters_filling,
-            });
-            
-            pb.inc(1);
-        }
-        
-        pb.finish_with_message("Processing complete");
-        
-        // Save to CSV if requested
-        if let Some(output_path) = output_csv_path {
-            save_results_to_csv(&results, output_path)?;
+            if kept % stride != 0 {
+                kept += 1;
+                continue;
+            }
+            kept += 1;
+
+            if let Some(max) = max_frames {
+                if frames_data.len() >= max {
+                    break;
+                }
+            }
+            frames_data.insert(frame_num, coords);
         }
-        
-        Ok(results)
+
+        Ok(frames_data)
     }
-    
+}
+
+impl Trajectory for PdbTrajectory {
+    fn read_pdb(&self, max_frames: Option<usize>) -> Result<TrajectoryData, String> {
+        self.read_pdb_range(max_frames, None, None, None)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn read_trajectory(
+        &self,
+        contacts: &[Contact],
+        cutoff_distance: f64,
+        beta: f64,
+        lambda: f64,
+        max_frames: Option<usize>,
+        sample_frames: Option<usize>,
+        sample_seed: Option<u64>,
+        start_frame: Option<i32>,
+        end_frame: Option<i32>,
+        stride: Option<usize>,
+        output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
+    ) -> Result<Vec<FrameResult>, String> {
+        let auto_path;
+        let output_csv_path = match output_csv_path {
+            Some(p) => Some(p),
+            None => {
+                let traj = Path::new(&self.file_path);
+                let base_name = traj.file_stem().and_then(|s| s.to_str()).unwrap_or("trajectory");
+                let traj_dir = traj.parent().unwrap_or(Path::new("."));
+                auto_path =
+                    traj_dir.join(format!("{}_parsed.{}", base_name, output_format.extension()));
+                Some(auto_path.as_path())
+            }
+        };
+        // Evaluate every frame across all available cores.
+        self.read_trajectory_parallel(
+            contacts,
+            cutoff_distance,
+            beta,
+            lambda,
+            max_frames,
+            output_csv_path,
+            use_cache,
+            None,
+            output_format,
+            sample_frames,
+            sample_seed,
+            start_frame,
+            end_frame,
+            stride,
+        )
+    }
+
     fn summarize_trajectory(
         &self,
         results: Option<&[FrameResult]>,
         window_size: usize,
         cutoff: Option<f64>,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
+        bootstrap: Option<usize>,
     ) -> Result<Vec<WindowSummary>, String> {
         // Load results if not provided
         let frame_results = if let Some(res) = results {
             res.to_vec()
         } else {
-            // Try to load from auto-generated CSV path
-            let base_name = Path::new(&self.file_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("trajectory");
-            let traj_dir = Path::new(&self.file_path)
-                .parent()
-                .unwrap_or(Path::new("."));
-            let csv_path = traj_dir.join(format!("{}_parsed.csv", base_name));
-            
-            if !csv_path.exists() {
-                return Err(format!(
-                    "Trajectory data not found. Please run read_trajectory() first or provide results. \
-                     Expected file: {}",
-                    csv_path.display()
-                ));
-            }
-            
-            load_results_from_csv(&csv_path)?
+            load_parsed_results(&self.file_path, use_cache)?
         };
-        
+
         if frame_results.is_empty() {
             return Err("Frame results are empty. Cannot create summary.".to_string());
         }
@@ -423,34 +725,51 @@ impl Trajectory for PdbTrajectory {
         let num_windows = (total_frames + window_size - 1) / window_size;  // Ceiling division
         
         let mut window_summaries = Vec::new();
-        
+
+        // One RNG drives all bootstrap resampling so the reported intervals are stable
+        // across runs for a given input.
+        let mut rng = bootstrap.map(|_| SplitMix64::new(0x243f6a8885a308d3));
+
         for window_idx in 0..num_windows {
             let start_idx = window_idx * window_size;
             let end_idx = (start_idx + window_size).min(total_frames);
-            
+
             let window_results = &sorted_results[start_idx..end_idx];
-            
+
             // Get the frame number for this window (use the first frame in the window)
             let frame_number = window_results[0].frame;
-            
+
             // Calculate mean fraction for each cluster in this window
             let mut cluster_means = HashMap::new();
-            
+            let mut cluster_ci: Option<HashMap<i32, (f64, f64)>> =
+                bootstrap.map(|_| HashMap::new());
+
             for cluster_num in &cluster_numbers {
                 let mut cluster_values = Vec::new();
-                
+
                 for result in window_results {
                     let fraction = result.clusters_filling.get(cluster_num).copied().unwrap_or(0.0);
                     cluster_values.push(fraction);
                 }
-                
+
                 // Calculate mean for this cluster in this window
                 let mean_fraction = if !cluster_values.is_empty() {
                     cluster_values.iter().sum::<f64>() / cluster_values.len() as f64
                 } else {
                     0.0
                 };
-                
+
+                // Bootstrap a 95% CI on the raw per-frame fractions (before any binary
+                // conversion) so the interval reflects the underlying occupancy.
+                if let (Some(resamples), Some(rng), Some(ci)) =
+                    (bootstrap, rng.as_mut(), cluster_ci.as_mut())
+                {
+                    ci.insert(
+                        *cluster_num,
+                        bootstrap_ci(&cluster_values, resamples, rng),
+                    );
+                }
+
                 // Apply binary conversion if cutoff is provided
                 let final_value = if let Some(cutoff_val) = cutoff {
                     if mean_fraction >= cutoff_val {
@@ -461,13 +780,14 @@ impl Trajectory for PdbTrajectory {
                 } else {
                     mean_fraction
                 };
-                
+
                 cluster_means.insert(*cluster_num, final_value);
             }
-            
+
             window_summaries.push(WindowSummary {
                 frame: frame_number,
                 cluster_means,
+                cluster_ci,
             });
         }
         
@@ -484,20 +804,21 @@ impl Trajectory for PdbTrajectory {
                 .parent()
                 .unwrap_or(Path::new("."));
             
-            // Use _summary_binary.csv if cutoff is provided, otherwise _summary.csv
+            // Use _summary_binary.{ext} if cutoff is provided, otherwise _summary.{ext}
+            let ext = output_format.extension();
             let suffix = if cutoff.is_some() {
-                "_summary_binary.csv"
+                format!("_summary_binary.{}", ext)
             } else {
-                "_summary.csv"
+                format!("_summary.{}", ext)
             };
             Some(traj_dir.join(format!("{}{}", base_name, suffix)))
         };
-        
-        // Save to CSV
+
+        // Save summaries in the requested format
         if let Some(output_path) = output_path_buf.as_deref() {
-            save_summary_to_csv(&window_summaries, &cluster_numbers, output_path)?;
+            save_summary(&window_summaries, &cluster_numbers, output_path, output_format, cutoff)?;
         }
-        
+
         Ok(window_summaries)
     }
     
@@ -506,32 +827,16 @@ impl Trajectory for PdbTrajectory {
         results: Option<&[FrameResult]>,
         window_size: usize,
         output_csv_path: Option<&Path>,
+        use_cache: bool,
+        output_format: OutputFormat,
     ) -> Result<Vec<SmoothedResult>, String> {
         // Load results if not provided
         let frame_results = if let Some(res) = results {
             res.to_vec()
         } else {
-            // Try to load from auto-generated CSV path
-            let base_name = Path::new(&self.file_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("trajectory");
-            let traj_dir = Path::new(&self.file_path)
-                .parent()
-                .unwrap_or(Path::new("."));
-            let csv_path = traj_dir.join(format!("{}_parsed.csv", base_name));
-            
-            if !csv_path.exists() {
-                return Err(format!(
-                    "Trajectory data not found. Please run read_trajectory() first or provide results. \
-                     Expected file: {}",
-                    csv_path.display()
-                ));
-            }
-            
-            load_results_from_csv(&csv_path)?
+            load_parsed_results(&self.file_path, use_cache)?
         };
-        
+
         if frame_results.is_empty() {
             return Err("Frame results are empty. Cannot smooth data.".to_string());
         }
@@ -597,14 +902,14 @@ impl Trajectory for PdbTrajectory {
             let traj_dir = Path::new(&self.file_path)
                 .parent()
                 .unwrap_or(Path::new("."));
-            Some(traj_dir.join(format!("{}_smoothed.csv", base_name)))
+            Some(traj_dir.join(format!("{}_smoothed.{}", base_name, output_format.extension())))
         };
-        
-        // Save to CSV
+
+        // Save smoothed results in the requested format
         if let Some(output_path) = output_path_buf.as_deref() {
-            save_smoothed_to_csv(&smoothed_results, &cluster_numbers, output_path)?;
+            save_smoothed(&smoothed_results, &cluster_numbers, output_path, output_format)?;
         }
-        
+
         Ok(smoothed_results)
     }
     
@@ -612,12 +917,16 @@ impl Trajectory for PdbTrajectory {
         &self,
         summary_csv_path: Option<&Path>,
         output_path: Option<&Path>,
+        hysteresis: Option<Hysteresis>,
     ) -> Result<Vec<i32>, String> {
-        // Determine summary CSV path
-        let csv_path_buf: std::path::PathBuf = if let Some(path) = summary_csv_path {
-            path.to_path_buf()
+        // Determine which summary file to load. An explicit path is dispatched by
+        // extension (`.parquet` is the typed columnar cache, anything else is the CSV
+        // text format); absent one, prefer the auto-generated `_summary_binary.parquet`
+        // cache over the legacy `_summary_binary.csv` so `determine_formation_order`
+        // reads typed columns instead of parsing stringified `{cluster: fraction}` maps.
+        let summary_data = if let Some(path) = summary_csv_path {
+            load_summary(path)?
         } else {
-            // Auto-generate from trajectory file path
             let base_name = Path::new(&self.file_path)
                 .file_stem()
                 .and_then(|s| s.to_str())
@@ -625,24 +934,145 @@ impl Trajectory for PdbTrajectory {
             let traj_dir = Path::new(&self.file_path)
                 .parent()
                 .unwrap_or(Path::new("."));
-            traj_dir.join(format!("{}_summary_binary.csv", base_name))
+
+            let parquet_path = traj_dir.join(format!("{}_summary_binary.parquet", base_name));
+            if parquet_path.exists() {
+                crate::parquet_io::load_summary_from_parquet(&parquet_path)?
+            } else {
+                let csv_path_buf = traj_dir.join(format!("{}_summary_binary.csv", base_name));
+                if !csv_path_buf.exists() {
+                    return Err(format!(
+                        "Summary file not found. Please run summarize_trajectory() with cutoff first. \
+                         Expected file: {} (or {})",
+                        csv_path_buf.display(),
+                        parquet_path.display()
+                    ));
+                }
+                load_summary_csv(&csv_path_buf)?
+            }
         };
-        
-        if !csv_path_buf.exists() {
-            return Err(format!(
-                "Summary CSV file not found. Please run summarize_trajectory() with cutoff first. \
-                 Expected file: {}",
-                csv_path_buf.display()
-            ));
+
+        // Determine the formation order from the (binary) window summaries
+        let formation_order = determine_formation_order(summary_data, hysteresis)?;
+
+        // Auto-generate output path if not provided
+        let output_path_buf: Option<std::path::PathBuf> = if let Some(path) = output_path {
+            Some(path.to_path_buf())
+        } else {
+            // Auto-generate from trajectory file path
+            let base_name = Path::new(&self.file_path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("trajectory");
+            let traj_dir = Path::new(&self.file_path)
+                .parent()
+                .unwrap_or(Path::new("."));
+            Some(traj_dir.join(format!("{}_class.txt", base_name)))
+        };
+
+        // Save to file
+        if let Some(output_path) = output_path_buf.as_deref() {
+            save_classification_to_file(&formation_order, output_path)?;
         }
-        
-        // Load summary CSV
-        let summary_data = load_summary_csv(&csv_path_buf)?;
-        
+
+        Ok(formation_order)
+    }
+}
+
+/// Debounced (Schmitt-trigger) formed/broken detection parameters for
+/// [`determine_formation_order`].
+///
+/// A single hard threshold records a cluster as broken the moment its occupancy dips below
+/// 1.0 for one window, so a cluster that flickers pollutes the formation order. The
+/// hysteresis mode instead treats a cluster as formed once its occupancy rises to `t_up`
+/// and only broken once it stays below `t_down` for more than `min_gap` consecutive
+/// windows; shorter sub-threshold runs are forward-filled from the last stable state.
+#[derive(Debug, Clone, Copy)]
+pub struct Hysteresis {
+    /// Upper threshold: occupancy at or above this marks a cluster formed.
+    pub t_up: f64,
+    /// Lower threshold: occupancy below this (for long enough) marks a cluster broken.
+    pub t_down: f64,
+    /// Maximum length of a sub-`t_down` run that is bridged (treated as still formed).
+    pub min_gap: usize,
+}
+
+impl Default for Hysteresis {
+    fn default() -> Self {
+        Self {
+            t_up: 1.0,
+            t_down: 0.5,
+            min_gap: 1,
+        }
+    }
+}
+
+/// Collapse a per-window occupancy series into a debounced formed/broken boolean series
+/// using a Schmitt-trigger with gap bridging (see [`Hysteresis`]).
+fn debounce_occupancy(values: &[f64], h: &Hysteresis) -> Vec<bool> {
+    let n = values.len();
+    let mut out = vec![false; n];
+    if n == 0 {
+        return out;
+    }
+
+    // Seed the stable state from the first window.
+    let mut formed = values[0] >= h.t_up;
+    // Count of consecutive sub-`t_down` windows while still nominally formed.
+    let mut below_run = 0usize;
+
+    for i in 0..n {
+        let v = values[i];
+        if formed {
+            if v < h.t_down {
+                below_run += 1;
+                if below_run > h.min_gap {
+                    // The sub-threshold run is long enough to count as a real break:
+                    // flip to broken and rewrite the tentatively-formed run.
+                    formed = false;
+                    for slot in out.iter_mut().take(i + 1).skip(i + 1 - below_run) {
+                        *slot = false;
+                    }
+                    below_run = 0;
+                } else {
+                    // Short dip: forward-fill the last stable (formed) state.
+                    out[i] = true;
+                }
+            } else {
+                // Recovered above the lower threshold; bridge the gap.
+                below_run = 0;
+                out[i] = true;
+            }
+        } else if v >= h.t_up {
+            formed = true;
+            below_run = 0;
+            out[i] = true;
+        }
+    }
+
+    out
+}
+
+/// Determine cluster formation order from (binary) window summaries.
+///
+/// Works backwards from the first window in which all clusters (excluding `cluster_0`)
+/// are formed, recording the order in which clusters were last broken, then reverses it
+/// to yield the order in which they formed. This is the shared core used both by the
+/// CSV-backed [`Trajectory::classify`] and by in-memory callers that already hold the
+/// summaries.
+///
+/// When `hysteresis` is `Some`, a debounced pre-processing pass (see [`Hysteresis`])
+/// rewrites each cluster's occupancy series into a clean formed/broken series before the
+/// backward walk, so thermal flickering in the contact occupancy no longer pollutes the
+/// order. When `None`, the legacy single hard threshold (`>= 1.0`) is used.
+pub fn determine_formation_order(
+    summary_data: Vec<WindowSummary>,
+    hysteresis: Option<Hysteresis>,
+) -> Result<Vec<i32>, String> {
         if summary_data.is_empty() {
             return Err("Summary data is empty. Cannot determine formation order.".to_string());
         }
-        
+
         // Get all cluster numbers (excluding cluster_0)
         let mut cluster_numbers: Vec<i32> = summary_data[0]
             .cluster_means
@@ -659,7 +1089,25 @@ impl Trajectory for PdbTrajectory {
         // Sort by frame number
         let mut sorted_data = summary_data;
         sorted_data.sort_by_key(|w| w.frame);
-        
+
+        // Optional debounced pre-processing: rewrite each cluster's occupancy series into a
+        // clean 1.0/0.0 formed/broken series so the hard-threshold backward walk below sees
+        // a noise-free signal.
+        if let Some(h) = hysteresis {
+            for &cluster_num in &cluster_numbers {
+                let series: Vec<f64> = sorted_data
+                    .iter()
+                    .map(|w| w.cluster_means.get(&cluster_num).copied().unwrap_or(0.0))
+                    .collect();
+                let debounced = debounce_occupancy(&series, &h);
+                for (window, &formed) in sorted_data.iter_mut().zip(debounced.iter()) {
+                    window
+                        .cluster_means
+                        .insert(cluster_num, if formed { 1.0 } else { 0.0 });
+                }
+            }
+        }
+
         // Step 2: Find first time all clusters (except cluster_0) are formed (all 1)
         let mut start_idx = None;
         let mut max_clusters_formed = 0;
@@ -768,40 +1216,1094 @@ impl Trajectory for PdbTrajectory {
         
         // Reverse the order to get formation order (last formed first in breaks = first formed last in formation)
         let formation_order: Vec<i32> = cluster_breaks_order.into_iter().rev().collect();
-        
-        // Auto-generate output path if not provided
-        let output_path_buf: Option<std::path::PathBuf> = if let Some(path) = output_path {
-            Some(path.to_path_buf())
-        } else {
-            // Auto-generate from trajectory file path
-            let base_name = Path::new(&self.file_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("trajectory");
-            let traj_dir = Path::new(&self.file_path)
-                .parent()
-                .unwrap_or(Path::new("."));
-            Some(traj_dir.join(format!("{}_class.txt", base_name)))
+
+        Ok(formation_order)
+}
+
+/// Min/max/IQR of a cluster's first-formation frame across replicas, from
+/// [`consensus_formation_order`]. A wide spread flags a cluster whose ordering relative to
+/// its neighbours is not well resolved across replicas.
+#[derive(Debug, Clone, Copy)]
+pub struct FormationSpread {
+    pub min: i32,
+    pub max: i32,
+    pub iqr: f64,
+}
+
+/// Consensus cluster-formation order aggregated across replicate trajectories, from
+/// [`consensus_formation_order`].
+#[derive(Debug, Clone)]
+pub struct ConsensusFormation {
+    /// Cluster numbers sorted by ascending median first-formation frame.
+    pub order: Vec<i32>,
+    /// Median first-formation frame for each cluster, across replicas.
+    pub median_frame: HashMap<i32, f64>,
+    /// Min/max/IQR of the first-formation frame across replicas, per cluster.
+    pub spread: HashMap<i32, FormationSpread>,
+}
+
+/// Determine a consensus cluster-formation order across replicate MD trajectories.
+///
+/// Loads each replica's binary summary CSV (as produced by [`save_summary_to_csv`] with a
+/// cutoff) and inner-joins them on the `frame` column, keeping only frames common to every
+/// replica so the per-replica series line up. Each replica is then walked independently to
+/// find the frame at which each cluster first becomes stably formed — the first window
+/// whose debounced occupancy (see [`debounce_occupancy`]) is `true` when `hysteresis` is
+/// set, or the first window at or above the legacy hard `>= 1.0` threshold otherwise. The
+/// per-cluster median of these first-formation frames across replicas gives the consensus
+/// order (ascending); the min/max/IQR of the same per-replica frames is returned as
+/// `spread` so callers can see which clusters have an ambiguous order. Saves the result
+/// alongside the usual `_class.txt` output as a `_consensus_class.csv`, auto-named from
+/// the first summary path when `output_path` is `None`.
+pub fn consensus_formation_order(
+    summary_csv_paths: &[impl AsRef<Path>],
+    hysteresis: Option<Hysteresis>,
+    output_path: Option<&Path>,
+) -> Result<ConsensusFormation, String> {
+    if summary_csv_paths.is_empty() {
+        return Err(
+            "No summary CSV paths provided. Cannot determine consensus formation order."
+                .to_string(),
+        );
+    }
+
+    let mut replicas: Vec<Vec<WindowSummary>> = Vec::with_capacity(summary_csv_paths.len());
+    for path in summary_csv_paths {
+        let mut summary = load_summary(path.as_ref())?;
+        summary.sort_by_key(|w| w.frame);
+        replicas.push(summary);
+    }
+
+    // Inner join on the frame column: keep only frames present in every replica.
+    let mut common_frames: std::collections::HashSet<i32> =
+        replicas[0].iter().map(|w| w.frame).collect();
+    for replica in &replicas[1..] {
+        let frames: std::collections::HashSet<i32> = replica.iter().map(|w| w.frame).collect();
+        common_frames.retain(|f| frames.contains(f));
+    }
+    if common_frames.is_empty() {
+        return Err("Summary CSVs share no common frames; cannot join replicas.".to_string());
+    }
+    for replica in &mut replicas {
+        replica.retain(|w| common_frames.contains(&w.frame));
+    }
+
+    // Cluster numbers present in the joined data (excluding cluster_0).
+    let mut cluster_numbers: Vec<i32> = replicas[0][0]
+        .cluster_means
+        .keys()
+        .filter(|&&k| k != 0)
+        .copied()
+        .collect();
+    cluster_numbers.sort();
+
+    if cluster_numbers.is_empty() {
+        return Err(
+            "No clusters found in summary data (excluding cluster_0). Cannot determine consensus formation order."
+                .to_string(),
+        );
+    }
+
+    // For each replica independently, find the first frame at which each cluster becomes
+    // stably formed, then pool the per-replica frames by cluster.
+    let mut per_cluster_frames: HashMap<i32, Vec<i32>> = HashMap::new();
+    for replica in &replicas {
+        for (cluster, frame) in first_formation_frames(replica, &cluster_numbers, hysteresis) {
+            per_cluster_frames.entry(cluster).or_default().push(frame);
+        }
+    }
+
+    let mut median_frame = HashMap::new();
+    let mut spread = HashMap::new();
+    for &cluster in &cluster_numbers {
+        let mut frames = match per_cluster_frames.remove(&cluster) {
+            Some(f) if !f.is_empty() => f,
+            _ => continue,
         };
-        
-        // Save to file
-        if let Some(output_path) = output_path_buf.as_deref() {
-            save_classification_to_file(&formation_order, output_path)?;
+        frames.sort();
+
+        let as_f64: Vec<f64> = frames.iter().map(|&f| f as f64).collect();
+        median_frame.insert(cluster, median(&as_f64));
+        spread.insert(
+            cluster,
+            FormationSpread {
+                min: frames[0],
+                max: frames[frames.len() - 1],
+                iqr: percentile(&as_f64, 75.0) - percentile(&as_f64, 25.0),
+            },
+        );
+    }
+
+    let mut order: Vec<i32> = median_frame.keys().copied().collect();
+    order.sort_by(|a, b| {
+        median_frame[a]
+            .partial_cmp(&median_frame[b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let consensus = ConsensusFormation {
+        order,
+        median_frame,
+        spread,
+    };
+
+    let output_path_buf: std::path::PathBuf = match output_path {
+        Some(path) => path.to_path_buf(),
+        None => consensus_output_path(summary_csv_paths[0].as_ref()),
+    };
+    save_consensus_to_csv(&consensus, &output_path_buf)?;
+
+    Ok(consensus)
+}
+
+/// For one replica's (sorted, frame-joined) window summaries, find the frame at which each
+/// cluster first becomes stably formed. Shares the debounced formed/broken series with
+/// [`determine_formation_order`]'s backward walk, but reads it forward and stops at the
+/// first `true`.
+fn first_formation_frames(
+    summary_data: &[WindowSummary],
+    cluster_numbers: &[i32],
+    hysteresis: Option<Hysteresis>,
+) -> HashMap<i32, i32> {
+    let mut first_formed = HashMap::new();
+    for &cluster_num in cluster_numbers {
+        let series: Vec<f64> = summary_data
+            .iter()
+            .map(|w| w.cluster_means.get(&cluster_num).copied().unwrap_or(0.0))
+            .collect();
+
+        let formed: Vec<bool> = match hysteresis {
+            Some(h) => debounce_occupancy(&series, &h),
+            None => series.iter().map(|&v| v >= 1.0).collect(),
+        };
+
+        if let Some(idx) = formed.iter().position(|&f| f) {
+            first_formed.insert(cluster_num, summary_data[idx].frame);
         }
-        
-        Ok(formation_order)
     }
+    first_formed
 }
 
-/// Save frame results to CSV file
-fn save_results_to_csv(results: &[FrameResult], output_path: &Path) -> Result<(), String> {
+/// Linear-interpolated median of an unsorted slice (sorts a clone internally).
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    percentile(&sorted, 50.0)
+}
+
+/// Derive the `_consensus_class.csv` output path from the first replica's summary CSV
+/// path, stripping a trailing `_summary_binary`/`_summary` suffix from its file stem.
+fn consensus_output_path(first_summary_path: &Path) -> std::path::PathBuf {
+    let base_name = first_summary_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("trajectory");
+    let base_name = base_name
+        .strip_suffix("_summary_binary")
+        .or_else(|| base_name.strip_suffix("_summary"))
+        .unwrap_or(base_name);
+    let dir = first_summary_path.parent().unwrap_or(Path::new("."));
+    dir.join(format!("{}_consensus_class.csv", base_name))
+}
+
+/// Save a [`ConsensusFormation`] to CSV: one row per cluster, in consensus order, with the
+/// median first-formation frame and its min/max/IQR spread across replicas.
+fn save_consensus_to_csv(consensus: &ConsensusFormation, output_path: &Path) -> Result<(), String> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+
     let mut writer = csv::Writer::from_path(output_path)
         .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
-    
+
+    writer
+        .write_record(&["cluster", "median_frame", "min_frame", "max_frame", "iqr"])
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+
+    for &cluster in &consensus.order {
+        let median_frame = consensus.median_frame.get(&cluster).copied().unwrap_or(0.0);
+        let spread = consensus
+            .spread
+            .get(&cluster)
+            .copied()
+            .unwrap_or(FormationSpread { min: 0, max: 0, iqr: 0.0 });
+
+        writer
+            .write_record(&[
+                cluster.to_string(),
+                median_frame.to_string(),
+                spread.min.to_string(),
+                spread.max.to_string(),
+                spread.iqr.to_string(),
+            ])
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
+
+    Ok(())
+}
+
+/// One distinct formation-order permutation's frequency across an ensemble of `_class.txt`
+/// files, from [`pathway_statistics`].
+#[derive(Debug, Clone)]
+pub struct PathwayFrequency {
+    /// The cluster formation order this permutation represents, first formed first.
+    pub order: Vec<i32>,
+    /// Number of replicas whose formation order matched this permutation exactly.
+    pub count: usize,
+    /// `count` divided by the total number of replicas.
+    pub fraction: f64,
+}
+
+/// A cluster's mean 1-based formation rank and its spread across an ensemble, from
+/// [`pathway_statistics`]. Replicas where the cluster never formed don't contribute a rank.
+#[derive(Debug, Clone, Copy)]
+pub struct RankSpread {
+    pub mean_rank: f64,
+    pub min_rank: usize,
+    pub max_rank: usize,
+    pub iqr: f64,
+}
+
+/// Cross-replica folding-pathway statistics, from [`pathway_statistics`].
+#[derive(Debug, Clone)]
+pub struct PathwayStatistics {
+    /// Distinct formation-order permutations, ranked by descending `count`.
+    pub pathways: Vec<PathwayFrequency>,
+    /// Mean formation rank and its spread for every cluster seen in any replica.
+    pub cluster_ranks: HashMap<i32, RankSpread>,
+}
+
+/// Aggregate `Classify`'s per-replica formation orders into an ensemble pathway report.
+///
+/// Each `_class.txt` in `class_txt_paths` holds one comma-separated formation order (see
+/// [`save_classification_to_file`]). Distinct orders are tallied by exact sequence match and
+/// ranked by occurrence; ties keep the order they were first seen in. Independently, each
+/// cluster's 1-based position within its replica's order is pooled across replicas to give a
+/// mean formation rank and a min/max/IQR spread, so a cluster that consistently forms early
+/// (low rank) can be distinguished from one whose ordering is noisy. Saves the pathway
+/// frequencies to `output_path` (or `{base}_pathways.csv` next to the first input) and the
+/// per-cluster ranks alongside it as `{base}_cluster_ranks.csv`.
+pub fn pathway_statistics(
+    class_txt_paths: &[impl AsRef<Path>],
+    output_path: Option<&Path>,
+) -> Result<PathwayStatistics, String> {
+    if class_txt_paths.is_empty() {
+        return Err(
+            "No class.txt paths provided. Cannot compute pathway statistics.".to_string(),
+        );
+    }
+
+    let mut orders = Vec::with_capacity(class_txt_paths.len());
+    for path in class_txt_paths {
+        orders.push(load_formation_order(path.as_ref())?);
+    }
+
+    // Tally distinct orders by exact sequence, preserving first-seen order among ties.
+    let mut seen: Vec<Vec<i32>> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    for order in &orders {
+        match seen.iter().position(|s| s == order) {
+            Some(idx) => counts[idx] += 1,
+            None => {
+                seen.push(order.clone());
+                counts.push(1);
+            }
+        }
+    }
+    let mut pathways: Vec<PathwayFrequency> = seen
+        .into_iter()
+        .zip(counts)
+        .map(|(order, count)| PathwayFrequency {
+            order,
+            count,
+            fraction: count as f64 / orders.len() as f64,
+        })
+        .collect();
+    pathways.sort_by(|a, b| b.count.cmp(&a.count));
+
+    // Pool each cluster's 1-based rank within its replica's order across every replica.
+    let mut per_cluster_ranks: HashMap<i32, Vec<usize>> = HashMap::new();
+    for order in &orders {
+        for (idx, &cluster) in order.iter().enumerate() {
+            per_cluster_ranks.entry(cluster).or_default().push(idx + 1);
+        }
+    }
+
+    let mut cluster_ranks = HashMap::new();
+    for (cluster, mut ranks) in per_cluster_ranks {
+        ranks.sort();
+        let as_f64: Vec<f64> = ranks.iter().map(|&r| r as f64).collect();
+        cluster_ranks.insert(
+            cluster,
+            RankSpread {
+                mean_rank: as_f64.iter().sum::<f64>() / as_f64.len() as f64,
+                min_rank: ranks[0],
+                max_rank: ranks[ranks.len() - 1],
+                iqr: percentile(&as_f64, 75.0) - percentile(&as_f64, 25.0),
+            },
+        );
+    }
+
+    let stats = PathwayStatistics {
+        pathways,
+        cluster_ranks,
+    };
+
+    let output_path_buf: std::path::PathBuf = match output_path {
+        Some(path) => path.to_path_buf(),
+        None => pathway_output_path(class_txt_paths[0].as_ref()),
+    };
+    save_pathway_frequencies_to_csv(&stats.pathways, &output_path_buf)?;
+    save_cluster_ranks_to_csv(&stats.cluster_ranks, &cluster_ranks_output_path(&output_path_buf))?;
+
+    Ok(stats)
+}
+
+/// Parse a `_class.txt` file (one comma-separated formation order, as written by
+/// [`save_classification_to_file`]) back into the ordered cluster numbers.
+fn load_formation_order(path: &Path) -> Result<Vec<i32>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read class file {}: {}", path.display(), e))?;
+    let line = contents.lines().next().unwrap_or("");
+    if line.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    line.split(',')
+        .map(|n| {
+            n.trim()
+                .parse::<i32>()
+                .map_err(|e| format!("Invalid cluster number '{}' in {}: {}", n, path.display(), e))
+        })
+        .collect()
+}
+
+/// Derive the `{base}_pathways.csv` output path from the first `_class.txt` path.
+fn pathway_output_path(first_class_path: &Path) -> std::path::PathBuf {
+    let base_name = first_class_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("trajectory");
+    let base_name = base_name.strip_suffix("_class").unwrap_or(base_name);
+    let dir = first_class_path.parent().unwrap_or(Path::new("."));
+    dir.join(format!("{}_pathways.csv", base_name))
+}
+
+/// Derive the per-cluster rank report path from the pathway frequency CSV's path.
+fn cluster_ranks_output_path(pathway_csv_path: &Path) -> std::path::PathBuf {
+    let base_name = pathway_csv_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("pathways");
+    let base_name = base_name.strip_suffix("_pathways").unwrap_or(base_name);
+    let dir = pathway_csv_path.parent().unwrap_or(Path::new("."));
+    dir.join(format!("{}_cluster_ranks.csv", base_name))
+}
+
+/// Save pathway frequencies to CSV with columns `order, count, fraction`, ranked by
+/// descending count. The order is rendered as `-`-joined cluster numbers (e.g. `2-1-3`).
+fn save_pathway_frequencies_to_csv(
+    pathways: &[PathwayFrequency],
+    output_path: &Path,
+) -> Result<(), String> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+
+    let mut writer = csv::Writer::from_path(output_path)
+        .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
+
+    writer
+        .write_record(&["order", "count", "fraction"])
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+
+    for pathway in pathways {
+        let order_str = pathway
+            .order
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("-");
+        writer
+            .write_record(&[order_str, pathway.count.to_string(), pathway.fraction.to_string()])
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
+
+    Ok(())
+}
+
+/// Save per-cluster mean formation rank and spread to CSV, sorted by ascending mean rank.
+fn save_cluster_ranks_to_csv(
+    cluster_ranks: &HashMap<i32, RankSpread>,
+    output_path: &Path,
+) -> Result<(), String> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+
+    let mut writer = csv::Writer::from_path(output_path)
+        .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
+
+    writer
+        .write_record(&["cluster", "mean_rank", "min_rank", "max_rank", "iqr"])
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+
+    let mut clusters: Vec<i32> = cluster_ranks.keys().copied().collect();
+    clusters.sort_by(|a, b| {
+        cluster_ranks[a]
+            .mean_rank
+            .partial_cmp(&cluster_ranks[b].mean_rank)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    for cluster in clusters {
+        let spread = cluster_ranks[&cluster];
+        writer
+            .write_record(&[
+                cluster.to_string(),
+                spread.mean_rank.to_string(),
+                spread.min_rank.to_string(),
+                spread.max_rank.to_string(),
+                spread.iqr.to_string(),
+            ])
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
+
+    Ok(())
+}
+
+/// Restrict a parsed trajectory to a frame or time interval before it is fed back into the
+/// summary/formation pipeline, without re-running the expensive contact computation.
+///
+/// `results` is assumed frame-sorted (as [`load_results_from_csv`] produces it): frames
+/// with index in `[start, end]` inclusive are kept, then every `stride`-th surviving frame
+/// is kept (`stride` of 1 or `None` keeps them all). `start`/`end` are frame indices when
+/// `dt` is `None`, or simulation time when `dt` (time per frame) is `Some` — converted to
+/// the nearest frame index via `frame = (time / dt).round()`. Because the input is
+/// frame-sorted, the scan stops as soon as a frame exceeds `end`.
+pub fn select_frame_range(
+    results: &[FrameResult],
+    start: Option<f64>,
+    end: Option<f64>,
+    stride: Option<usize>,
+    dt: Option<f64>,
+) -> Vec<FrameResult> {
+    let to_frame = |t: f64| -> i32 {
+        match dt {
+            Some(dt) if dt > 0.0 => (t / dt).round() as i32,
+            _ => t.round() as i32,
+        }
+    };
+
+    let start_frame = start.map(to_frame);
+    let end_frame = end.map(to_frame);
+    let stride = stride.unwrap_or(1).max(1);
+
+    let mut selected = Vec::new();
+    let mut kept = 0usize;
+    for result in results {
+        if let Some(s) = start_frame {
+            if result.frame < s {
+                continue;
+            }
+        }
+        if let Some(e) = end_frame {
+            if result.frame > e {
+                // The input is frame-sorted, so nothing further can fall in range.
+                break;
+            }
+        }
+        if kept % stride == 0 {
+            selected.push(result.clone());
+        }
+        kept += 1;
+    }
+
+    selected
+}
+
+/// Extension point for the `BatchRead` CLI command to aggregate per-replica results
+/// without holding every replica's [`FrameResult`]s in memory at once.
+///
+/// [`batch_process_replicas`] drives replicas through [`process_one`](Self::process_one)
+/// concurrently over a bounded worker pool (the same channel-backed pattern as
+/// [`PdbTrajectory::read_trajectory_pooled`]), then immediately folds each replica's
+/// results into the accumulator as they complete — only the results of replicas still in
+/// flight or awaiting folding are held, never the whole batch's results together.
+pub trait BatchOperator {
+    /// Running aggregate threaded through [`fold_into`](Self::fold_into).
+    type Acc: Send;
+
+    /// Process one replica trajectory end-to-end, returning its per-frame results.
+    fn process_one(&self, path: &Path) -> Result<Vec<FrameResult>, String>;
+
+    /// Seed a fresh accumulator before the first replica is folded in.
+    fn create_accumulator(&self) -> Self::Acc;
+
+    /// Fold one replica's results into the running accumulator.
+    fn fold_into(&self, acc: &mut Self::Acc, results: &[FrameResult]);
+}
+
+/// Process replica trajectories in `paths` concurrently with a bounded worker pool,
+/// folding each replica's results into a [`BatchOperator::Acc`] as soon as it completes.
+///
+/// `num_workers` caps parallelism (0 = use all available cores, capped at one worker per
+/// replica). `progress` is invoked after each replica finishes with
+/// `(done, total, path)` so callers can report status as files complete.
+pub fn batch_process_replicas<Op, F>(
+    paths: &[std::path::PathBuf],
+    op: &Op,
+    num_workers: usize,
+    mut progress: F,
+) -> Result<Op::Acc, String>
+where
+    Op: BatchOperator + Sync,
+    F: FnMut(usize, usize, &Path),
+{
+    if paths.is_empty() {
+        return Err("No replica trajectories to process.".to_string());
+    }
+
+    let workers = if num_workers == 0 {
+        thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    } else {
+        num_workers
+    }
+    .max(1)
+    .min(paths.len());
+
+    let (job_tx, job_rx) = mpsc::channel::<std::path::PathBuf>();
+    let job_rx = std::sync::Arc::new(std::sync::Mutex::new(job_rx));
+    let (res_tx, res_rx) =
+        mpsc::channel::<(std::path::PathBuf, Result<Vec<FrameResult>, String>)>();
+
+    let mut acc = op.create_accumulator();
+    let total = paths.len();
+
+    thread::scope(|scope| -> Result<(), String> {
+        for _ in 0..workers {
+            let job_rx = std::sync::Arc::clone(&job_rx);
+            let res_tx = res_tx.clone();
+            scope.spawn(move || loop {
+                let path = {
+                    let guard = job_rx.lock().unwrap();
+                    guard.recv()
+                };
+                let path = match path {
+                    Ok(p) => p,
+                    Err(_) => break,
+                };
+                let result = op.process_one(&path);
+                if res_tx.send((path, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        // Drop our own sender so the result channel closes once workers finish.
+        drop(res_tx);
+
+        for path in paths {
+            job_tx
+                .send(path.clone())
+                .map_err(|e| format!("Failed to dispatch replica to worker pool: {}", e))?;
+        }
+        drop(job_tx);
+
+        let mut done = 0usize;
+        while let Ok((path, result)) = res_rx.recv() {
+            let results = result?;
+            op.fold_into(&mut acc, &results);
+            done += 1;
+            progress(done, total, &path);
+        }
+
+        Ok(())
+    })?;
+
+    Ok(acc)
+}
+
+/// Default [`BatchOperator`] for `BatchRead`: runs the standard contact/Q pipeline on each
+/// replica (writing its own `_parsed.{ext}` alongside it, with the extension of
+/// `output_format`), then folds the results into the
+/// running per-frame mean of each cluster's filling fraction across replicas.
+pub struct MeanClusterFillingOperator<'a> {
+    pub contacts: &'a [Contact],
+    pub cutoff_distance: f64,
+    pub beta: f64,
+    pub lambda: f64,
+    pub max_frames: Option<usize>,
+    pub output_format: OutputFormat,
+}
+
+/// Running per-frame, per-cluster `(sum, count)` of filling fractions across replicas,
+/// accumulated by [`MeanClusterFillingOperator`]; divide to get the mean at any point.
+#[derive(Debug, Clone, Default)]
+pub struct MeanClusterFillingAcc {
+    pub per_frame: HashMap<i32, HashMap<i32, (f64, usize)>>,
+}
+
+impl BatchOperator for MeanClusterFillingOperator<'_> {
+    type Acc = MeanClusterFillingAcc;
+
+    fn process_one(&self, path: &Path) -> Result<Vec<FrameResult>, String> {
+        let base = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("trajectory");
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let output_path = dir.join(format!("{}_parsed.{}", base, self.output_format.extension()));
+
+        PdbTrajectory::new(path).read_trajectory(
+            self.contacts,
+            self.cutoff_distance,
+            self.beta,
+            self.lambda,
+            self.max_frames,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(&output_path),
+            true,
+            self.output_format,
+        )
+    }
+
+    fn create_accumulator(&self) -> Self::Acc {
+        MeanClusterFillingAcc::default()
+    }
+
+    fn fold_into(&self, acc: &mut Self::Acc, results: &[FrameResult]) {
+        for result in results {
+            let frame_entry = acc.per_frame.entry(result.frame).or_default();
+            for (&cluster, &fraction) in &result.clusters_filling {
+                let slot = frame_entry.entry(cluster).or_insert((0.0, 0));
+                slot.0 += fraction;
+                slot.1 += 1;
+            }
+        }
+    }
+}
+
+/// Save a [`MeanClusterFillingAcc`] to CSV: one row per frame (sorted), with a
+/// `cluster_N` column per cluster holding the mean filling fraction across replicas.
+pub fn save_batch_aggregate_to_csv(
+    acc: &MeanClusterFillingAcc,
+    output_path: &Path,
+) -> Result<(), String> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+
+    let mut cluster_numbers: Vec<i32> = acc
+        .per_frame
+        .values()
+        .flat_map(|clusters| clusters.keys().copied())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    cluster_numbers.sort();
+
+    let mut frames: Vec<i32> = acc.per_frame.keys().copied().collect();
+    frames.sort();
+
+    let mut writer = csv::Writer::from_path(output_path)
+        .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
+
+    let mut header = vec!["frame".to_string()];
+    header.extend(cluster_numbers.iter().map(|c| format!("cluster_{}", c)));
+    writer
+        .write_record(&header)
+        .map_err(|e| format!("Failed to write CSV header: {}", e))?;
+
+    for frame in frames {
+        let clusters = &acc.per_frame[&frame];
+        let mut row = vec![frame.to_string()];
+        for &cluster in &cluster_numbers {
+            let mean = clusters
+                .get(&cluster)
+                .map(|&(sum, count)| if count > 0 { sum / count as f64 } else { 0.0 })
+                .unwrap_or(0.0);
+            row.push(mean.to_string());
+        }
+        writer
+            .write_record(&row)
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
+
+    Ok(())
+}
+
+/// Minimal SplitMix64 generator, used for reproducible reservoir sampling. Mirrors the
+/// deterministic stream in [`crate::tsne`] so the crate keeps a single RNG idiom rather
+/// than pulling in an external `rand` dependency.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Non-deterministic seed for reservoir sampling when the caller does not supply one.
+fn default_sample_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x9e3779b97f4a7c15)
+}
+
+/// Bootstrap a 95% confidence interval for the mean of `values`.
+///
+/// Draws `resamples` bootstrap resamples (sampling `values` with replacement to the
+/// original length), takes the mean of each, and returns the 2.5th and 97.5th percentiles
+/// of the resampled means. Returns `(0.0, 0.0)` for an empty input.
+fn bootstrap_ci(values: &[f64], resamples: usize, rng: &mut SplitMix64) -> (f64, f64) {
+    if values.is_empty() || resamples == 0 {
+        return (0.0, 0.0);
+    }
+    let n = values.len();
+    let mut means = Vec::with_capacity(resamples);
+    for _ in 0..resamples {
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let idx = (rng.next_u64() % n as u64) as usize;
+            sum += values[idx];
+        }
+        means.push(sum / n as f64);
+    }
+    means.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    (percentile(&means, 2.5), percentile(&means, 97.5))
+}
+
+/// Linear-interpolated percentile of an already-sorted slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    if lo == hi {
+        sorted[lo]
+    } else {
+        let frac = rank - lo as f64;
+        sorted[lo] * (1.0 - frac) + sorted[hi] * frac
+    }
+}
+
+/// Evaluate native-contact formation for a single frame.
+///
+/// Pure function over a frame's CA coordinates: a contact (i, j) is counted as formed
+/// when the CA-CA distance is below `r_native * cutoff_distance`. Returns the per-frame
+/// `q` (fraction of all native contacts formed) and the per-cluster filling fractions,
+/// with every cluster represented even when no contacts formed.
+fn evaluate_frame(
+    frame: i32,
+    residue_coords: &FrameData,
+    contacts: &[Contact],
+    cluster_sizes: &HashMap<i32, usize>,
+    total_contacts: usize,
+    cutoff_distance: f64,
+    beta: f64,
+    lambda: f64,
+) -> FrameResult {
+    let mut existing_contacts = Vec::new();
+    let mut cluster_counts: HashMap<i32, usize> = HashMap::new();
+    // Accumulators for the smooth Best–Hummer Q.
+    let mut soft_sum = 0.0;
+    let mut cluster_soft_sums: HashMap<i32, f64> = HashMap::new();
+
+    // Check each native contact
+    for contact in contacts {
+        // Check if both residues exist in the structure
+        if let (Some(coord_i), Some(coord_j)) =
+            (residue_coords.get(&contact.i), residue_coords.get(&contact.j))
+        {
+            // Calculate distance between CA atoms
+            let distance = coord_i.distance_to(coord_j);
+
+            // Smooth (Best–Hummer) fractional formation for this native pair.
+            let soft = 1.0 / (1.0 + (beta * (distance - lambda * contact.r)).exp());
+            soft_sum += soft;
+            *cluster_soft_sums.entry(contact.cluster).or_insert(0.0) += soft;
+
+            // Check if contact exists (distance < r_native * cutoff_distance)
+            if distance < contact.r * cutoff_distance {
+                existing_contacts.push((contact.i, contact.j));
+                *cluster_counts.entry(contact.cluster).or_insert(0) += 1;
+            }
+        }
+    }
+
+    // Calculate q (fraction of native contacts existing)
+    let q = if total_contacts > 0 {
+        existing_contacts.len() as f64 / total_contacts as f64
+    } else {
+        0.0
+    };
+
+    // Calculate soft q (mean smooth formation over all native contacts)
+    let q_soft = if total_contacts > 0 {
+        soft_sum / total_contacts as f64
+    } else {
+        0.0
+    };
+
+    // Calculate clusters_filling (fraction of contacts in each cluster)
+    let mut clusters_filling = HashMap::new();
+    for (cluster_num, count) in cluster_counts.iter() {
+        let cluster_size = cluster_sizes.get(cluster_num).copied().unwrap_or(0);
+        if cluster_size > 0 {
+            clusters_filling.insert(*cluster_num, *count as f64 / cluster_size as f64);
+        } else {
+            clusters_filling.insert(*cluster_num, 0.0);
+        }
+    }
+
+    // Calculate soft per-cluster filling the same way
+    let mut clusters_filling_soft = HashMap::new();
+    for (cluster_num, soft) in cluster_soft_sums.iter() {
+        let cluster_size = cluster_sizes.get(cluster_num).copied().unwrap_or(0);
+        if cluster_size > 0 {
+            clusters_filling_soft.insert(*cluster_num, *soft / cluster_size as f64);
+        } else {
+            clusters_filling_soft.insert(*cluster_num, 0.0);
+        }
+    }
+
+    // Ensure all clusters are represented (even if no contacts formed)
+    for cluster_num in cluster_sizes.keys() {
+        clusters_filling.entry(*cluster_num).or_insert(0.0);
+        clusters_filling_soft.entry(*cluster_num).or_insert(0.0);
+    }
+
+    FrameResult {
+        frame,
+        contacts: existing_contacts.len(),
+        q,
+        q_soft,
+        contact_list: existing_contacts,
+        clusters_filling,
+        clusters_filling_soft,
+    }
+}
+
+/// Find every residue pair within `cutoff` Å in a single frame using a uniform grid cell
+/// list, skipping backbone-adjacent pairs (`|i - j| < 3`).
+///
+/// The grid cell side equals the cutoff, so any two residues closer than the cutoff fall
+/// in the same or adjacent cells; each residue is therefore only tested against the
+/// occupants of its own cell and the 26 neighbours. Pairs are returned once, in sorted
+/// `(i, j)` order with `i < j`.
+fn detect_frame_contacts(coords: &FrameData, cutoff: f64) -> Vec<(i32, i32)> {
+    let cell_of = |c: &crate::structure::Coordinate| {
+        (
+            (c.x / cutoff).floor() as i64,
+            (c.y / cutoff).floor() as i64,
+            (c.z / cutoff).floor() as i64,
+        )
+    };
+
+    // Bucket residues into grid cells.
+    let mut grid: HashMap<(i64, i64, i64), Vec<i32>> = HashMap::new();
+    for (&res, coord) in coords {
+        grid.entry(cell_of(coord)).or_default().push(res);
+    }
+
+    let mut pairs = Vec::new();
+    for (&res_i, coord_i) in coords {
+        let (cx, cy, cz) = cell_of(coord_i);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(bucket) = grid.get(&(cx + dx, cy + dy, cz + dz)) else {
+                        continue;
+                    };
+                    for &res_j in bucket {
+                        // Record each unordered pair once (i < j) and skip backbone
+                        // neighbours.
+                        if res_j <= res_i || (res_i - res_j).abs() < 3 {
+                            continue;
+                        }
+                        if coord_i.distance_to(&coords[&res_j]) < cutoff {
+                            pairs.push((res_i, res_j));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pairs.sort();
+    pairs
+}
+
+/// Format a cluster map as `"{cluster: fraction, ...}"` with cluster keys sorted.
+fn format_clusters_map(map: &HashMap<i32, f64>) -> String {
+    let mut cluster_entries: Vec<(i32, f64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+    cluster_entries.sort_by_key(|(k, _)| *k);
+    format!(
+        "{{{}}}",
+        cluster_entries
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Save frame results in the requested [`OutputFormat`] (plain CSV, columnar Parquet, or the
+/// compact [`crate::bin_io`] binary layout).
+fn save_results(
+    results: &[FrameResult],
+    output_path: &Path,
+    format: OutputFormat,
+) -> Result<(), String> {
+    match format {
+        OutputFormat::Csv => save_results_to_csv(results, output_path),
+        OutputFormat::Parquet => crate::parquet_io::save_results_to_parquet(results, output_path),
+        OutputFormat::Bin => crate::bin_io::save_results_to_bin(results, output_path),
+    }
+}
+
+/// Save window summaries in the requested [`OutputFormat`]. `cutoff` (the binary-conversion
+/// threshold, if any) is recorded in the Parquet schema metadata so the file is
+/// self-describing about how it was produced.
+fn save_summary(
+    summaries: &[WindowSummary],
+    cluster_numbers: &[i32],
+    output_path: &Path,
+    format: OutputFormat,
+    cutoff: Option<f64>,
+) -> Result<(), String> {
+    match format {
+        OutputFormat::Csv => save_summary_to_csv(summaries, cluster_numbers, output_path),
+        OutputFormat::Parquet => crate::parquet_io::save_summary_to_parquet(
+            summaries,
+            cluster_numbers,
+            output_path,
+            cutoff,
+        ),
+        OutputFormat::Bin => Err(
+            "Bin output is only supported for per-frame results (Read/BatchRead), not window summaries"
+                .to_string(),
+        ),
+    }
+}
+
+/// Save smoothed results in the requested [`OutputFormat`].
+fn save_smoothed(
+    smoothed: &[SmoothedResult],
+    cluster_numbers: &[i32],
+    output_path: &Path,
+    format: OutputFormat,
+) -> Result<(), String> {
+    match format {
+        OutputFormat::Csv => save_smoothed_to_csv(smoothed, cluster_numbers, output_path),
+        OutputFormat::Parquet => {
+            crate::parquet_io::save_smoothed_to_parquet(smoothed, cluster_numbers, output_path)
+        }
+        OutputFormat::Bin => Err(
+            "Bin output is only supported for per-frame results (Read/BatchRead), not smoothed data"
+                .to_string(),
+        ),
+    }
+}
+
+/// Load the parsed per-frame results for the downstream `summarize`/`smooth` steps.
+///
+/// Resolves the auto-generated sidecar next to the trajectory, preferring (in order): a
+/// columnar `{base}_parsed.parquet` written by the Parquet backend, the compact
+/// `{base}_parsed.bin` written by [`crate::bin_io`], then the bincode `.traj.cache` when it
+/// is fresher than the parsed CSV, then the `{base}_parsed.csv` itself. `use_cache` gates the
+/// `.traj.cache` shortcut exactly as before.
+fn load_parsed_results(trajectory_file: &str, use_cache: bool) -> Result<Vec<FrameResult>, String> {
+    let base_name = Path::new(trajectory_file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("trajectory");
+    let traj_dir = Path::new(trajectory_file).parent().unwrap_or(Path::new("."));
+
+    // A Parquet parse is typed and self-describing, so prefer it when present.
+    let parquet_path = traj_dir.join(format!("{}_parsed.parquet", base_name));
+    if parquet_path.exists() {
+        return crate::parquet_io::load_results_from_parquet(&parquet_path);
+    }
+
+    let bin_path = traj_dir.join(format!("{}_parsed.bin", base_name));
+    if bin_path.exists() {
+        return crate::bin_io::load_results_from_bin(&bin_path);
+    }
+
+    let csv_path = traj_dir.join(format!("{}_parsed.csv", base_name));
+    if !csv_path.exists() {
+        return Err(format!(
+            "Trajectory data not found. Please run read_trajectory() first or provide results. \
+             Expected file: {}",
+            csv_path.display()
+        ));
+    }
+
+    // Prefer the bincode cache sidecar when it is fresher than the parsed CSV.
+    let cache_path = crate::cache::cache_path(trajectory_file);
+    match use_cache
+        .then(|| crate::cache::load_cache_if_fresh(&cache_path, &csv_path))
+        .flatten()
+    {
+        Some(cached) => Ok(cached),
+        None => load_results_from_csv(&csv_path),
+    }
+}
+
+/// Save frame results to CSV file.
+///
+/// When `output_path` ends in `.gz` or `.lz4`, the CSV is streamed through a gzip or LZ4
+/// encoder (see [`crate::compressed_io`]) — the `contact_list`/cluster-map columns can get
+/// very large for long trajectories, so compression cuts on-disk size several-fold. A
+/// plain `.csv` path is unaffected.
+fn save_results_to_csv(results: &[FrameResult], output_path: &Path) -> Result<(), String> {
+    let mut writer =
+        csv::Writer::from_writer(crate::compressed_io::writer_for_path(output_path)?);
+
     // Write header
-    writer.write_record(&["frame", "contacts", "q", "contact_list", "clusters_filling"])
+    writer.write_record(&["frame", "contacts", "q", "q_soft", "contact_list", "clusters_filling", "clusters_filling_soft"])
         .map_err(|e| format!("Failed to write CSV header: {}", e))?;
-    
+
     // Write each row
     for result in results {
         // Format contact_list as string: "[(i1, j1), (i2, j2), ...]"
@@ -813,46 +2315,41 @@ fn save_results_to_csv(results: &[FrameResult], output_path: &Path) -> Result<()
                 .collect::<Vec<_>>()
                 .join(", ")
         );
-        
+
         // Format clusters_filling as string: "{cluster1: fraction1, cluster2: fraction2, ...}"
-        let mut cluster_entries: Vec<(i32, f64)> = result.clusters_filling.iter()
-            .map(|(k, v)| (*k, *v))
-            .collect();
-        cluster_entries.sort_by_key(|(k, _)| *k);
-        let clusters_filling_str = format!(
-            "{{{}}}",
-            cluster_entries
-                .iter()
-                .map(|(k, v)| format!("{}: {}", k, v))
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
-        
+        let clusters_filling_str = format_clusters_map(&result.clusters_filling);
+        let clusters_filling_soft_str = format_clusters_map(&result.clusters_filling_soft);
+
         writer.write_record(&[
             result.frame.to_string(),
             result.contacts.to_string(),
             result.q.to_string(),
+            result.q_soft.to_string(),
             contact_list_str,
             clusters_filling_str,
+            clusters_filling_soft_str,
         ])
         .map_err(|e| format!("Failed to write CSV row: {}", e))?;
     }
-    
-    writer.flush()
-        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
-    
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?
+        .finish()?;
+
     Ok(())
 }
 
-/// Save smoothed results to CSV file
+/// Save smoothed results to CSV file. Supports the same `.gz`/`.lz4` transparent
+/// compression as [`save_results_to_csv`].
 fn save_smoothed_to_csv(
     smoothed: &[SmoothedResult],
     cluster_numbers: &[i32],
     output_path: &Path,
 ) -> Result<(), String> {
-    let mut writer = csv::Writer::from_path(output_path)
-        .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
-    
+    let mut writer =
+        csv::Writer::from_writer(crate::compressed_io::writer_for_path(output_path)?);
+
     // Write header: frame, q_smooth, cluster_0_smooth, cluster_1_smooth, ...
     let mut header = vec!["frame".to_string(), "q_smooth".to_string()];
     for cluster_num in cluster_numbers {
@@ -871,18 +2368,31 @@ fn save_smoothed_to_csv(
         writer.write_record(&row)
             .map_err(|e| format!("Failed to write CSV row: {}", e))?;
     }
-    
-    writer.flush()
-        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
-    
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?
+        .finish()?;
+
     Ok(())
 }
 
-/// Load summary CSV file (from summarize_trajectory)
+/// Load window summaries from an explicit path, dispatching on extension: `.parquet` goes
+/// through the typed columnar [`crate::parquet_io::load_summary_from_parquet`] cache,
+/// anything else through the text [`load_summary_csv`].
+fn load_summary(path: &Path) -> Result<Vec<WindowSummary>, String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("parquet") => crate::parquet_io::load_summary_from_parquet(path),
+        _ => load_summary_csv(path),
+    }
+}
+
+/// Load summary CSV file (from summarize_trajectory). Transparently decompresses a
+/// `.gz`/`.lz4` summary the same way [`load_results_from_csv`] does.
 fn load_summary_csv(csv_path: &Path) -> Result<Vec<WindowSummary>, String> {
-    let mut reader = csv::Reader::from_path(csv_path)
-        .map_err(|e| format!("Failed to open summary CSV file {}: {}", csv_path.display(), e))?;
-    
+    let mut reader =
+        csv::Reader::from_reader(crate::compressed_io::reader_for_path(csv_path)?);
+
     let mut summaries = Vec::new();
     let headers = reader.headers()
         .map_err(|e| format!("Failed to read CSV headers: {}", e))?;
@@ -926,9 +2436,10 @@ fn load_summary_csv(csv_path: &Path) -> Result<Vec<WindowSummary>, String> {
         summaries.push(WindowSummary {
             frame,
             cluster_means,
+            cluster_ci: None,
         });
     }
-    
+
     Ok(summaries)
 }
 
@@ -959,13 +2470,36 @@ fn save_classification_to_file(formation_order: &[i32], output_path: &Path) -> R
     Ok(())
 }
 
-/// Load frame results from CSV file
-fn load_results_from_csv(csv_path: &Path) -> Result<Vec<FrameResult>, String> {
-    let mut reader = csv::Reader::from_path(csv_path)
-        .map_err(|e| format!("Failed to open CSV file {}: {}", csv_path.display(), e))?;
-    
+/// Load frame results from CSV file.
+///
+/// A `.gz`/`.lz4` path (see [`crate::compressed_io`]) is streamed through the matching
+/// decoder, since a compressed file can't be mmapped as plain bytes. Otherwise, for large
+/// parsed-result CSVs (above [`crate::mmap_csv::MMAP_THRESHOLD_BYTES`]) the file is
+/// memory-mapped and iterated over the mapped slice instead of being read through a
+/// buffered file handle; small files keep the plain path.
+pub fn load_results_from_csv(csv_path: &Path) -> Result<Vec<FrameResult>, String> {
+    if crate::compressed_io::is_compressed(csv_path) {
+        let reader =
+            csv::ReaderBuilder::new().from_reader(crate::compressed_io::reader_for_path(csv_path)?);
+        parse_results_records(reader)
+    } else if crate::mmap_csv::file_len(csv_path).unwrap_or(0) >= crate::mmap_csv::MMAP_THRESHOLD_BYTES
+    {
+        let mmap = crate::mmap_csv::mmap_csv(csv_path)?;
+        parse_results_records(csv::ReaderBuilder::new().from_reader(&mmap[..]))
+    } else {
+        let reader = csv::ReaderBuilder::new()
+            .from_path(csv_path)
+            .map_err(|e| format!("Failed to open CSV file {}: {}", csv_path.display(), e))?;
+        parse_results_records(reader)
+    }
+}
+
+/// Parse `FrameResult` rows from any CSV reader (file-backed or mmap-backed).
+fn parse_results_records<R: std::io::Read>(
+    mut reader: csv::Reader<R>,
+) -> Result<Vec<FrameResult>, String> {
     let mut results = Vec::new();
-    
+
     for record_result in reader.records() {
         let record = record_result
             .map_err(|e| format!("Failed to read CSV record: {}", e))?;
@@ -984,21 +2518,34 @@ fn load_results_from_csv(csv_path: &Path) -> Result<Vec<FrameResult>, String> {
             .ok_or("Missing q column")?
             .parse()
             .map_err(|e| format!("Failed to parse q: {}", e))?;
-        
-        // Parse contact_list (skip for now, not needed for summary)
-        // Parse clusters_filling
-        let clusters_filling_str = record.get(4)
-            .ok_or("Missing clusters_filling column")?;
-        
+
+        // q_soft, contact_list, clusters_filling, clusters_filling_soft follow. q_soft and
+        // the soft cluster map are optional so older parsed CSVs (without them) still load.
+        let legacy = record.len() < 7;
+        let q_soft: f64 = if legacy {
+            q
+        } else {
+            record.get(3).and_then(|s| s.parse().ok()).unwrap_or(q)
+        };
+
         // Parse clusters_filling string like "{0: 0.5, 1: 0.3, ...}"
+        let clusters_filling_str = record.get(if legacy { 4 } else { 5 })
+            .ok_or("Missing clusters_filling column")?;
         let clusters_filling = parse_clusters_filling(clusters_filling_str)?;
-        
+
+        let clusters_filling_soft = match record.get(6) {
+            Some(s) if !legacy && !s.is_empty() => parse_clusters_filling(s)?,
+            _ => clusters_filling.clone(),
+        };
+
         results.push(FrameResult {
             frame,
             contacts,
             q,
+            q_soft,
             contact_list: Vec::new(),  // Not needed for summary
             clusters_filling,
+            clusters_filling_soft,
         });
     }
     
@@ -1045,23 +2592,33 @@ fn parse_clusters_filling(s: &str) -> Result<HashMap<i32, f64>, String> {
     Ok(clusters)
 }
 
-/// Save window summaries to CSV file
+/// Save window summaries to CSV file. Supports the same `.gz`/`.lz4` transparent
+/// compression as [`save_results_to_csv`].
 fn save_summary_to_csv(
     summaries: &[WindowSummary],
     cluster_numbers: &[i32],
     output_path: &Path,
 ) -> Result<(), String> {
-    let mut writer = csv::Writer::from_path(output_path)
-        .map_err(|e| format!("Failed to create CSV file {}: {}", output_path.display(), e))?;
-    
-    // Write header: frame, cluster_0, cluster_1, ...
+    let mut writer =
+        csv::Writer::from_writer(crate::compressed_io::writer_for_path(output_path)?);
+
+    // When bootstrap CIs were computed, append a low/high column per cluster.
+    let has_ci = summaries.first().is_some_and(|s| s.cluster_ci.is_some());
+
+    // Write header: frame, cluster_0, cluster_1, [cluster_0_ci_low, cluster_0_ci_high, ...]
     let mut header = vec!["frame".to_string()];
     for cluster_num in cluster_numbers {
         header.push(format!("cluster_{}", cluster_num));
     }
+    if has_ci {
+        for cluster_num in cluster_numbers {
+            header.push(format!("cluster_{}_ci_low", cluster_num));
+            header.push(format!("cluster_{}_ci_high", cluster_num));
+        }
+    }
     writer.write_record(&header)
         .map_err(|e| format!("Failed to write CSV header: {}", e))?;
-    
+
     // Write each row
     for summary in summaries {
         let mut row = vec![summary.frame.to_string()];
@@ -1069,13 +2626,270 @@ fn save_summary_to_csv(
             let value = summary.cluster_means.get(cluster_num).copied().unwrap_or(0.0);
             row.push(value.to_string());
         }
+        if has_ci {
+            for cluster_num in cluster_numbers {
+                let (low, high) = summary
+                    .cluster_ci
+                    .as_ref()
+                    .and_then(|ci| ci.get(cluster_num).copied())
+                    .unwrap_or((0.0, 0.0));
+                row.push(low.to_string());
+                row.push(high.to_string());
+            }
+        }
         writer.write_record(&row)
             .map_err(|e| format!("Failed to write CSV row: {}", e))?;
     }
-    
-    writer.flush()
-        .map_err(|e| format!("Failed to flush CSV file: {}", e))?;
-    
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to flush CSV file: {}", e))?
+        .finish()?;
+
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom_line(x: f64, y: f64, z: f64) -> String {
+        let mut line = vec![b' '; 54];
+        line[0..4].copy_from_slice(b"ATOM");
+        line[12..16].copy_from_slice(b" CA ");
+        line[22..26].copy_from_slice(b"   1");
+        line[30..38].copy_from_slice(format!("{:>8.3}", x).as_bytes());
+        line[38..46].copy_from_slice(format!("{:>8.3}", y).as_bytes());
+        line[46..54].copy_from_slice(format!("{:>8.3}", z).as_bytes());
+        String::from_utf8(line).unwrap()
+    }
+
+    /// Build a multi-model PDB with `nmodels` frames, each a single CA atom whose
+    /// coordinates encode its model number so a sampled frame's identity can be checked.
+    fn build_pdb(nmodels: i32) -> String {
+        let mut out = String::new();
+        for model in 1..=nmodels {
+            out.push_str(&format!("MODEL     {:>4}\n", model));
+            out.push_str(&atom_line(model as f64, 0.0, 0.0));
+            out.push('\n');
+            out.push_str("ENDMDL\n");
+        }
+        out
+    }
+
+    #[test]
+    fn reservoir_sample_is_deterministic_and_uniform_in_count() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("trajectory_test_reservoir_{}.pdb", std::process::id()));
+        std::fs::write(&path, build_pdb(20)).unwrap();
+        let trajectory = PdbTrajectory::new(&path);
+
+        let first = trajectory.read_pdb_sampled(5, Some(42)).unwrap();
+        let second = trajectory.read_pdb_sampled(5, Some(42)).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(first.len(), 5);
+        let mut first_models: Vec<i32> = first.keys().copied().collect();
+        first_models.sort();
+        let mut second_models: Vec<i32> = second.keys().copied().collect();
+        second_models.sort();
+        assert_eq!(first_models, second_models, "same seed must give the same sample");
+        assert!(first_models.iter().all(|&m| (1..=20).contains(&m)));
+        assert_eq!(first_models.iter().collect::<std::collections::HashSet<_>>().len(), 5);
+    }
+
+    #[test]
+    fn reservoir_sample_of_zero_is_empty() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("trajectory_test_reservoir_zero_{}.pdb", std::process::id()));
+        std::fs::write(&path, build_pdb(5)).unwrap();
+        let trajectory = PdbTrajectory::new(&path);
+
+        let sampled = trajectory.read_pdb_sampled(0, Some(1)).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(sampled.is_empty());
+    }
+
+    #[test]
+    fn debounce_occupancy_bridges_short_gaps_and_breaks_long_ones() {
+        let h = Hysteresis {
+            t_up: 1.0,
+            t_down: 0.5,
+            min_gap: 1,
+        };
+        // Formed, one-window dip below t_down (bridged), formed again, then a 2-window dip
+        // (exceeds min_gap) that should register as a real break.
+        let series = [1.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0];
+        let formed = debounce_occupancy(&series, &h);
+        assert_eq!(formed, vec![true, true, true, true, false, false, true]);
+    }
+
+    #[test]
+    fn debounce_occupancy_empty_input() {
+        let h = Hysteresis::default();
+        assert!(debounce_occupancy(&[], &h).is_empty());
+    }
+
+    #[test]
+    fn bootstrap_ci_is_seeded_and_deterministic() {
+        let values = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let mut rng_a = SplitMix64::new(7);
+        let mut rng_b = SplitMix64::new(7);
+        let (low_a, high_a) = bootstrap_ci(&values, 200, &mut rng_a);
+        let (low_b, high_b) = bootstrap_ci(&values, 200, &mut rng_b);
+
+        assert_eq!((low_a, high_a), (low_b, high_b), "same seed must give the same CI");
+        assert!(low_a <= high_a);
+        // The resampled mean can never fall outside the original data's range.
+        assert!(low_a >= 1.0 && high_a <= 5.0);
+    }
+
+    #[test]
+    fn bootstrap_ci_of_empty_values_is_zero() {
+        let mut rng = SplitMix64::new(1);
+        assert_eq!(bootstrap_ci(&[], 100, &mut rng), (0.0, 0.0));
+    }
+
+    fn window(frame: i32, cluster_means: &[(i32, f64)]) -> WindowSummary {
+        WindowSummary {
+            frame,
+            cluster_means: cluster_means.iter().copied().collect(),
+            cluster_ci: None,
+        }
+    }
+
+    #[test]
+    fn consensus_formation_order_picks_median_across_replicas() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let path_a = dir.join(format!("trajectory_test_consensus_a_{}.csv", pid));
+        let path_b = dir.join(format!("trajectory_test_consensus_b_{}.csv", pid));
+
+        // Cluster 1 forms at frame 1 in both replicas; cluster 2 forms at frame 2 in
+        // replica A and frame 3 in replica B, so its median first-formation frame is 2.5.
+        let replica_a = vec![
+            window(1, &[(0, 1.0), (1, 1.0), (2, 0.0)]),
+            window(2, &[(0, 1.0), (1, 1.0), (2, 1.0)]),
+            window(3, &[(0, 1.0), (1, 1.0), (2, 1.0)]),
+        ];
+        let replica_b = vec![
+            window(1, &[(0, 1.0), (1, 1.0), (2, 0.0)]),
+            window(2, &[(0, 1.0), (1, 1.0), (2, 0.0)]),
+            window(3, &[(0, 1.0), (1, 1.0), (2, 1.0)]),
+        ];
+        save_summary_to_csv(&replica_a, &[0, 1, 2], &path_a).unwrap();
+        save_summary_to_csv(&replica_b, &[0, 1, 2], &path_b).unwrap();
+
+        let result = consensus_formation_order(&[path_a.clone(), path_b.clone()], None, None);
+        std::fs::remove_file(&path_a).ok();
+        std::fs::remove_file(&path_b).ok();
+        std::fs::remove_file(consensus_output_path(&path_a)).ok();
+
+        let consensus = result.unwrap();
+        assert_eq!(consensus.order, vec![1, 2]);
+        assert_eq!(consensus.median_frame[&1], 1.0);
+        assert_eq!(consensus.median_frame[&2], 2.5);
+    }
+
+    #[test]
+    fn pathway_statistics_tallies_distinct_orders_and_ranks() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let path_a = dir.join(format!("trajectory_test_pathway_a_{}.txt", pid));
+        let path_b = dir.join(format!("trajectory_test_pathway_b_{}.txt", pid));
+        let path_c = dir.join(format!("trajectory_test_pathway_c_{}.txt", pid));
+
+        save_classification_to_file(&[1, 2, 3], &path_a).unwrap();
+        save_classification_to_file(&[1, 2, 3], &path_b).unwrap();
+        save_classification_to_file(&[2, 1, 3], &path_c).unwrap();
+
+        let result = pathway_statistics(&[path_a.clone(), path_b.clone(), path_c.clone()], None);
+        std::fs::remove_file(&path_a).ok();
+        std::fs::remove_file(&path_b).ok();
+        std::fs::remove_file(&path_c).ok();
+        std::fs::remove_file(pathway_output_path(&path_a)).ok();
+        std::fs::remove_file(cluster_ranks_output_path(&pathway_output_path(&path_a))).ok();
+
+        let stats = result.unwrap();
+        assert_eq!(stats.pathways.len(), 2);
+        assert_eq!(stats.pathways[0].order, vec![1, 2, 3]);
+        assert_eq!(stats.pathways[0].count, 2);
+        assert_eq!(stats.pathways[1].order, vec![2, 1, 3]);
+        assert_eq!(stats.pathways[1].count, 1);
+
+        // Cluster 1 is first in two replicas and second in one: mean rank (1+1+2)/3.
+        let rank1 = stats.cluster_ranks[&1];
+        assert!((rank1.mean_rank - 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rank1.min_rank, 1);
+        assert_eq!(rank1.max_rank, 2);
+    }
+
+    #[test]
+    fn frb1_round_trips_frame_results() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("trajectory_test_frb1_{}.bin", std::process::id()));
+
+        let results = vec![
+            FrameResult {
+                frame: 1,
+                contacts: 10,
+                q: 0.5,
+                q_soft: 0.6,
+                contact_list: vec![(1, 2), (2, 3)],
+                clusters_filling: [(0, 1.0), (1, 0.5)].into_iter().collect(),
+                clusters_filling_soft: [(0, 1.0), (1, 0.4)].into_iter().collect(),
+            },
+            FrameResult {
+                frame: 2,
+                contacts: 8,
+                q: 0.7,
+                q_soft: 0.65,
+                contact_list: vec![],
+                clusters_filling: [(0, 0.0), (1, 1.0)].into_iter().collect(),
+                clusters_filling_soft: [(0, 0.0), (1, 0.9)].into_iter().collect(),
+            },
+        ];
+
+        crate::bin_io::save_results_to_bin(&results, &path).unwrap();
+        let loaded = crate::bin_io::load_results_from_bin(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].frame, 1);
+        assert_eq!(loaded[0].q, 0.5);
+        assert_eq!(loaded[0].clusters_filling, results[0].clusters_filling);
+        assert_eq!(loaded[1].frame, 2);
+        assert_eq!(loaded[1].clusters_filling, results[1].clusters_filling);
+        // Columns not stored by FRB1 fall back to the documented defaults.
+        assert_eq!(loaded[0].contacts, 0);
+        assert!(loaded[0].contact_list.is_empty());
+        assert_eq!(loaded[0].q_soft, loaded[0].q);
+    }
+
+    #[test]
+    fn select_frame_range_applies_inclusive_bounds_then_strides() {
+        let results: Vec<FrameResult> = (1..=10)
+            .map(|frame| FrameResult {
+                frame,
+                contacts: 0,
+                q: 0.0,
+                q_soft: 0.0,
+                contact_list: vec![],
+                clusters_filling: std::collections::HashMap::new(),
+                clusters_filling_soft: std::collections::HashMap::new(),
+            })
+            .collect();
+
+        // Inclusive bounds: frames 3..=8 survive the range filter.
+        let ranged = select_frame_range(&results, Some(3.0), Some(8.0), None, None);
+        let ranged_frames: Vec<i32> = ranged.iter().map(|r| r.frame).collect();
+        assert_eq!(ranged_frames, vec![3, 4, 5, 6, 7, 8]);
+
+        // Stride is applied to the filtered sequence's position, not the raw frame number:
+        // with stride 2 over [3..=8], every other *surviving* frame is kept (3, 5, 7).
+        let strided = select_frame_range(&results, Some(3.0), Some(8.0), Some(2), None);
+        let strided_frames: Vec<i32> = strided.iter().map(|r| r.frame).collect();
+        assert_eq!(strided_frames, vec![3, 5, 7]);
+    }
+}
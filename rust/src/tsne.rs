@@ -0,0 +1,420 @@
+use crate::trajectory::FrameResult;
+
+/// A single frame's position in the 2D t-SNE embedding of the folding landscape.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TsnePoint {
+    pub frame: i32,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Tunable parameters for the t-SNE embedding. Defaults follow the usual t-SNE choices
+/// (perplexity 30, 1000 iterations) and are exposed so callers can trade quality for speed
+/// on very long trajectories.
+#[derive(Debug, Clone)]
+pub struct TsneParams {
+    /// Keep every `stride`-th frame (1 = all frames). The pairwise step is O(N²), so
+    /// long trajectories should subsample.
+    pub stride: usize,
+    /// Target perplexity; the per-point bandwidth σ_i is binary-searched to match it.
+    pub perplexity: f64,
+    /// Number of gradient-descent iterations.
+    pub iterations: usize,
+    /// Gradient-descent learning rate.
+    pub learning_rate: f64,
+}
+
+impl Default for TsneParams {
+    fn default() -> Self {
+        Self {
+            stride: 1,
+            perplexity: 30.0,
+            iterations: 1000,
+            learning_rate: 200.0,
+        }
+    }
+}
+
+/// Number of early-exaggeration iterations and the factor applied to `p_ij` during them.
+const EARLY_EXAGGERATION_ITERS: usize = 100;
+const EARLY_EXAGGERATION: f64 = 4.0;
+/// Momentum schedule: `MOMENTUM_INITIAL` for the first `MOMENTUM_SWITCH_ITER` steps,
+/// `MOMENTUM_FINAL` thereafter.
+const MOMENTUM_INITIAL: f64 = 0.5;
+const MOMENTUM_FINAL: f64 = 0.8;
+const MOMENTUM_SWITCH_ITER: usize = 250;
+
+/// Embed each frame's native-contact-formation vector into 2D with a self-contained,
+/// Barnes–Hut-free t-SNE, producing a map of the folding landscape.
+///
+/// Each frame is treated as a high-dimensional point whose coordinates are the 0/1
+/// formation state of every native pair that forms at some point in the (subsampled)
+/// trajectory. The algorithm (1) computes squared pairwise distances, (2) binary-searches
+/// a per-point Gaussian bandwidth σ_i so the conditional-distribution perplexity matches
+/// [`TsneParams::perplexity`], (3) symmetrizes to `p_ij`, (4) initializes the 2D points
+/// from a small deterministic Gaussian, and (5) runs momentum gradient descent on the KL
+/// divergence with Student-t low-dimensional affinities and early exaggeration.
+///
+/// Returns one [`TsnePoint`] per retained frame, in increasing frame order.
+pub fn tsne_embedding(
+    results: &[FrameResult],
+    params: &TsneParams,
+) -> Result<Vec<TsnePoint>, String> {
+    let stride = params.stride.max(1);
+
+    // Subsample frames in increasing frame order.
+    let mut sorted: Vec<&FrameResult> = results.iter().collect();
+    sorted.sort_by_key(|r| r.frame);
+    let sampled: Vec<&FrameResult> = sorted.into_iter().step_by(stride).collect();
+
+    let n = sampled.len();
+    if n < 3 {
+        return Err(format!(
+            "Need at least 3 frames for a t-SNE embedding, got {} (try a smaller stride).",
+            n
+        ));
+    }
+
+    // Build the feature universe: every native pair that forms in any retained frame,
+    // in a stable sorted order, mapped to a column index.
+    let mut pair_index: std::collections::HashMap<(i32, i32), usize> =
+        std::collections::HashMap::new();
+    let mut pairs: Vec<(i32, i32)> = Vec::new();
+    for result in &sampled {
+        for &pair in &result.contact_list {
+            if !pair_index.contains_key(&pair) {
+                pair_index.insert(pair, pairs.len());
+                pairs.push(pair);
+            }
+        }
+    }
+    if pairs.is_empty() {
+        return Err(
+            "No contacts formed in the retained frames; nothing to embed. Did you call \
+             analyze() with a populated contact list?"
+                .to_string(),
+        );
+    }
+    let dim = pairs.len();
+
+    // Dense 0/1 contact-formation vectors, one row per retained frame.
+    let mut vectors = vec![0.0f64; n * dim];
+    for (row, result) in sampled.iter().enumerate() {
+        for &pair in &result.contact_list {
+            let col = pair_index[&pair];
+            vectors[row * dim + col] = 1.0;
+        }
+    }
+
+    // (1) Squared pairwise distances between frame vectors.
+    let distances = squared_distances(&vectors, n, dim);
+
+    // (2) + (3) High-dimensional affinities p_ij.
+    let p = high_dim_affinities(&distances, n, params.perplexity);
+
+    // (4) Initialize the 2D points from a small deterministic Gaussian.
+    let mut y = init_embedding(n);
+
+    // (5) Gradient descent on the KL divergence.
+    gradient_descent(&p, &mut y, n, params);
+
+    Ok(sampled
+        .iter()
+        .enumerate()
+        .map(|(i, result)| TsnePoint {
+            frame: result.frame,
+            x: y[i * 2],
+            y: y[i * 2 + 1],
+        })
+        .collect())
+}
+
+/// Dense row-major matrix of squared Euclidean distances between the `n` feature vectors.
+fn squared_distances(vectors: &[f64], n: usize, dim: usize) -> Vec<f64> {
+    let mut distances = vec![0.0f64; n * n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let mut sum = 0.0;
+            for d in 0..dim {
+                let diff = vectors[i * dim + d] - vectors[j * dim + d];
+                sum += diff * diff;
+            }
+            distances[i * n + j] = sum;
+            distances[j * n + i] = sum;
+        }
+    }
+    distances
+}
+
+/// Binary-search tolerance and iteration cap for matching the target perplexity.
+const PERPLEXITY_TOLERANCE: f64 = 1e-5;
+const PERPLEXITY_MAX_ITERS: usize = 50;
+
+/// Build the symmetric high-dimensional affinities `p_ij` from the squared-distance
+/// matrix. For each point `i` the Gaussian bandwidth is binary-searched so the perplexity
+/// (`2^entropy`) of the conditional distribution `p_{j|i} ∝ exp(−d²_ij / 2σ_i²)` matches
+/// the target, then the conditionals are symmetrized to `p_ij = (p_{j|i} + p_{i|j}) / 2N`.
+fn high_dim_affinities(distances: &[f64], n: usize, perplexity: f64) -> Vec<f64> {
+    let log_target = perplexity.ln();
+    let mut conditional = vec![0.0f64; n * n];
+
+    for i in 0..n {
+        // Binary-search beta = 1 / (2 σ_i²) to hit the target entropy.
+        let mut beta = 1.0f64;
+        let mut beta_min = f64::NEG_INFINITY;
+        let mut beta_max = f64::INFINITY;
+
+        for _ in 0..PERPLEXITY_MAX_ITERS {
+            // Unnormalized conditional affinities for this beta (excluding j == i).
+            let mut sum = 0.0;
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let p = (-distances[i * n + j] * beta).exp();
+                conditional[i * n + j] = p;
+                sum += p;
+            }
+            if sum <= 0.0 {
+                sum = 1e-12;
+            }
+
+            // Shannon entropy of the normalized distribution, H = log(sum) + beta * <d²>.
+            let mut dist_sum = 0.0;
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                dist_sum += distances[i * n + j] * conditional[i * n + j];
+            }
+            let entropy = sum.ln() + beta * dist_sum / sum;
+
+            let diff = entropy - log_target;
+            if diff.abs() < PERPLEXITY_TOLERANCE {
+                break;
+            }
+            // Entropy increases with σ (decreases with beta): adjust the bracket.
+            if diff > 0.0 {
+                beta_min = beta;
+                beta = if beta_max.is_infinite() {
+                    beta * 2.0
+                } else {
+                    (beta + beta_max) / 2.0
+                };
+            } else {
+                beta_max = beta;
+                beta = if beta_min.is_infinite() {
+                    beta / 2.0
+                } else {
+                    (beta + beta_min) / 2.0
+                };
+            }
+        }
+
+        // Normalize the conditional row to sum to 1.
+        let mut sum = 0.0;
+        for j in 0..n {
+            if i != j {
+                sum += conditional[i * n + j];
+            }
+        }
+        if sum <= 0.0 {
+            sum = 1e-12;
+        }
+        for j in 0..n {
+            if i != j {
+                conditional[i * n + j] /= sum;
+            }
+        }
+    }
+
+    // Symmetrize: p_ij = (p_{j|i} + p_{i|j}) / 2N.
+    let mut p = vec![0.0f64; n * n];
+    let norm = 2.0 * n as f64;
+    for i in 0..n {
+        for j in 0..n {
+            let value = (conditional[i * n + j] + conditional[j * n + i]) / norm;
+            p[i * n + j] = value.max(1e-12);
+        }
+    }
+    p
+}
+
+/// Initialize the 2D embedding from a small Gaussian (std 1e-4), using a deterministic
+/// Box–Muller transform over a SplitMix64 stream so repeated runs are stable.
+fn init_embedding(n: usize) -> Vec<f64> {
+    let mut state: u64 = 0x9e3779b97f4a7c15;
+    let mut next_uniform = || {
+        // SplitMix64 step, mapped to (0, 1).
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        ((z >> 11) as f64 + 1.0) / ((1u64 << 53) as f64 + 2.0)
+    };
+
+    let mut y = vec![0.0f64; n * 2];
+    for value in y.iter_mut() {
+        let u1 = next_uniform();
+        let u2 = next_uniform();
+        let gaussian = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        *value = gaussian * 1e-4;
+    }
+    y
+}
+
+/// Run momentum gradient descent on the KL divergence between `p_ij` and the Student-t
+/// low-dimensional affinities `q_ij ∝ (1 + ‖y_i − y_j‖²)⁻¹`, updating the embedding `y`
+/// in place. Applies early exaggeration to `p_ij` for the first
+/// [`EARLY_EXAGGERATION_ITERS`] iterations and ramps the momentum partway through.
+fn gradient_descent(p: &[f64], y: &mut [f64], n: usize, params: &TsneParams) {
+    let mut velocity = vec![0.0f64; n * 2];
+    let mut grad = vec![0.0f64; n * 2];
+
+    for iter in 0..params.iterations {
+        let exaggeration = if iter < EARLY_EXAGGERATION_ITERS {
+            EARLY_EXAGGERATION
+        } else {
+            1.0
+        };
+        let momentum = if iter < MOMENTUM_SWITCH_ITER {
+            MOMENTUM_INITIAL
+        } else {
+            MOMENTUM_FINAL
+        };
+
+        // Unnormalized Student-t affinities and their normalizer.
+        let mut num = vec![0.0f64; n * n];
+        let mut q_sum = 0.0;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let dx = y[i * 2] - y[j * 2];
+                let dy = y[i * 2 + 1] - y[j * 2 + 1];
+                let value = 1.0 / (1.0 + dx * dx + dy * dy);
+                num[i * n + j] = value;
+                num[j * n + i] = value;
+                q_sum += 2.0 * value;
+            }
+        }
+        if q_sum <= 0.0 {
+            q_sum = 1e-12;
+        }
+
+        // dC/dy_i = 4 · Σ_j (p_ij − q_ij)(y_i − y_j)(1 + ‖y_i − y_j‖²)⁻¹.
+        for i in 0..n {
+            let mut gx = 0.0;
+            let mut gy = 0.0;
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let q = num[i * n + j] / q_sum;
+                let mult = (exaggeration * p[i * n + j] - q) * num[i * n + j];
+                gx += mult * (y[i * 2] - y[j * 2]);
+                gy += mult * (y[i * 2 + 1] - y[j * 2 + 1]);
+            }
+            grad[i * 2] = 4.0 * gx;
+            grad[i * 2 + 1] = 4.0 * gy;
+        }
+
+        // Momentum update.
+        for k in 0..n * 2 {
+            velocity[k] = momentum * velocity[k] - params.learning_rate * grad[k];
+            y[k] += velocity[k];
+        }
+
+        // Re-center the embedding on the origin each step.
+        let mut mean_x = 0.0;
+        let mut mean_y = 0.0;
+        for i in 0..n {
+            mean_x += y[i * 2];
+            mean_y += y[i * 2 + 1];
+        }
+        mean_x /= n as f64;
+        mean_y /= n as f64;
+        for i in 0..n {
+            y[i * 2] -= mean_x;
+            y[i * 2 + 1] -= mean_y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(frame: i32, contact_list: Vec<(i32, i32)>) -> FrameResult {
+        FrameResult {
+            frame,
+            contacts: contact_list.len(),
+            q: 0.0,
+            q_soft: 0.0,
+            contact_list,
+            clusters_filling: std::collections::HashMap::new(),
+            clusters_filling_soft: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn embedding_keeps_two_well_separated_clusters_apart() {
+        // Two groups of frames with disjoint, internally-identical contact patterns:
+        // within a group the high-dimensional distance is 0, between groups it is large.
+        let cluster_a = vec![(1, 2), (1, 3)];
+        let cluster_b = vec![(4, 5), (4, 6)];
+        let results = vec![
+            frame(1, cluster_a.clone()),
+            frame(2, cluster_a.clone()),
+            frame(3, cluster_a.clone()),
+            frame(4, cluster_b.clone()),
+            frame(5, cluster_b.clone()),
+            frame(6, cluster_b.clone()),
+        ];
+        let params = TsneParams {
+            iterations: 250,
+            ..TsneParams::default()
+        };
+
+        let points = tsne_embedding(&results, &params).expect("embedding should succeed");
+        assert_eq!(points.len(), 6);
+
+        let dist = |a: &TsnePoint, b: &TsnePoint| {
+            let dx = a.x - b.x;
+            let dy = a.y - b.y;
+            (dx * dx + dy * dy).sqrt()
+        };
+
+        let by_frame = |f: i32| points.iter().find(|p| p.frame == f).unwrap();
+        let within_a = dist(by_frame(1), by_frame(2)) + dist(by_frame(2), by_frame(3));
+        let within_b = dist(by_frame(4), by_frame(5)) + dist(by_frame(5), by_frame(6));
+        let between = dist(by_frame(1), by_frame(4)) + dist(by_frame(3), by_frame(6));
+
+        assert!(
+            between > within_a && between > within_b,
+            "expected the two clusters to stay apart: within_a={within_a}, within_b={within_b}, between={between}"
+        );
+    }
+
+    #[test]
+    fn embedding_is_deterministic_for_a_fixed_input() {
+        let results = vec![
+            frame(1, vec![(1, 2)]),
+            frame(2, vec![(1, 2), (2, 3)]),
+            frame(3, vec![(2, 3)]),
+            frame(4, vec![(1, 2), (2, 3), (3, 4)]),
+        ];
+        let params = TsneParams {
+            iterations: 100,
+            ..TsneParams::default()
+        };
+
+        let first = tsne_embedding(&results, &params).expect("first run should succeed");
+        let second = tsne_embedding(&results, &params).expect("second run should succeed");
+
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.frame, b.frame);
+            assert_eq!(a.x, b.x);
+            assert_eq!(a.y, b.y);
+        }
+    }
+}